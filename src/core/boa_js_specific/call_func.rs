@@ -1,21 +1,42 @@
-use boa_engine::{Context, JsValue};
+use crate::core::boa_js_specific::host_rc::{template_result, HostPaths};
+use crate::core::boa_js_specific::js_convert_judge::binary_content;
+use crate::core::boa_js_specific::settle_promise::settle_promise;
+use crate::core::data_hold::data_struct::FileContent;
+use boa_engine::object::ObjectInitializer;
+use boa_engine::property::Attribute;
+use boa_engine::{js_string, Context, JsObject, JsValue};
 
-pub fn call_function(context: &mut Context, func_name: &str, content: &str) -> Result<String, String> {
+/// Call a path function with `args` (content, vars, earlier outputs) and convert its result to
+/// text, or to raw bytes for a `Uint8Array`, `ArrayBuffer` or `{base64}` result. A
+/// `{path, content}` result also returns the path that overrides `fn.path`.
+pub fn call_function(
+    context: &mut Context,
+    functions: &JsObject,
+    func_name: &str,
+    args: &[JsValue],
+    host_paths: &HostPaths,
+) -> Result<(Option<String>, FileContent), String> {
     use boa_engine::JsString;
 
-    let global = context.global_object();
     let func_key = JsString::from(func_name);
 
-    let func = global
+    let func = functions
         .get(func_key, context)
         .map_err(|e| format!("Failed to get function: {}", e))?;
 
-    let content_value = JsValue::from(JsString::from(content));
-    let args = [content_value];
-
     match func.as_callable() {
-        Some(callable) => match callable.call(&JsValue::undefined(), &args, context) {
+        Some(callable) => match callable.call(&JsValue::undefined(), args, context) {
             Ok(result) => {
+                // `async` functions and returned promises: wait for the value
+                let result = settle_promise(context, result)?;
+                let (path, result) = split_path_override(context, result)?;
+                if let Some(bytes) = binary_content(context, &result)? {
+                    return Ok((path, FileContent::Binary(bytes)));
+                }
+                // `{template, data}`: render the template instead
+                if let Some(rendered) = template_result(context, &result, host_paths)? {
+                    return Ok((path, FileContent::Text(rendered)));
+                }
                 // Properly convert result based on type
                 let output = match result {
                     // String: extract without quotes
@@ -38,10 +59,48 @@ pub fn call_function(context: &mut Context, func_name: &str, content: &str) -> R
                     // Objects/Arrays: use display
                     _ => result.display().to_string(),
                 };
-                Ok(output)
+                Ok((path, FileContent::Text(output)))
             },
             Err(e) => Err(format!("{}", e)),
         },
         None => Err(format!("{} is not callable", func_name)),
     }
-}
\ No newline at end of file
+}
+
+/// `{path, content}` becomes the path and the content; `{path, base64}` (the shape `fn.after`
+/// outputs use for binary files) becomes the path and a `{base64}` wrapper; `{path, template, data}`
+/// keeps the object so the template is rendered. Anything else has no path override.
+fn split_path_override(context: &mut Context, result: JsValue) -> Result<(Option<String>, JsValue), String> {
+    let Some(object) = result.as_object().filter(|object| !object.is_callable() && !object.is_array())
+    else {
+        return Ok((None, result));
+    };
+    let path = object
+        .get(js_string!("path"), context)
+        .map_err(|e| format!("Failed to get path: {}", e))?;
+    let Some(path) = path.as_string().map(|path| path.to_std_string_escaped()) else {
+        return Ok((None, result));
+    };
+    if object
+        .has_property(js_string!("template"), context)
+        .map_err(|e| format!("Failed to get template: {}", e))?
+    {
+        return Ok((Some(path), result));
+    }
+    let content = object
+        .get(js_string!("content"), context)
+        .map_err(|e| format!("Failed to get content: {}", e))?;
+    if !content.is_undefined() {
+        return Ok((Some(path), content));
+    }
+    let base64 = object
+        .get(js_string!("base64"), context)
+        .map_err(|e| format!("Failed to get base64: {}", e))?;
+    if base64.is_string() {
+        let wrapper = ObjectInitializer::new(context)
+            .property(js_string!("base64"), base64, Attribute::all())
+            .build();
+        return Ok((Some(path), wrapper.into()));
+    }
+    Err(format!("returned {{path: \"{}\"}} without content or base64", path))
+}
@@ -0,0 +1,174 @@
+use crate::core::boa_js_specific::host_console::ParserConsole;
+use crate::core::boa_js_specific::host_rc::{register_rc_api, HostPaths};
+use crate::core::boa_js_specific::parser_module_loader::ParserModuleLoader;
+use crate::core::data_hold::data_struct::Config;
+use boa_engine::builtins::promise::PromiseState;
+use boa_engine::module::Module;
+use boa_engine::property::PropertyKey;
+use boa_engine::{Context, JsObject, Script, Source};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// A JavaScript file parsed either as a classic script or as an ES module
+enum ParsedFile {
+    Script(Script),
+    Module(Module),
+}
+
+/// Create a context and evaluate the parser in it, as a script or as an ES module.
+/// `.mjs` files are always modules; other files are modules only when they do not parse as a
+/// script (top-level `import`/`export`), so `import` text inside template literals is harmless.
+/// Script parsers get the exports of every library file as globals; module parsers import them.
+/// `console` and `rc` are installed before anything runs, so top-level code can use them too.
+/// Returns the object its functions live on: the global object for scripts,
+/// the module namespace (its exports) for modules.
+pub fn evaluate_parser(
+    js_code: &str,
+    js_file_str: &str,
+    generation_config: &Config,
+    console: &ParserConsole,
+) -> Result<(Context, JsObject), String> {
+    let js_file_path = canonical_parser_path(js_file_str);
+    let parser_dir = js_file_path.parent().unwrap_or(Path::new("/"));
+
+    let lib_dirs = generation_config.libs.clone().unwrap_or_default();
+    let loader = Rc::new(ParserModuleLoader::new(parser_dir, &lib_dirs));
+    let mut context = Context::builder()
+        .module_loader(loader.clone())
+        .build()
+        .map_err(|e| format!("Failed to create JavaScript context: {}", e))?;
+    console.register(&mut context)?;
+    let host_paths = HostPaths::for_parser(generation_config, parser_dir);
+    register_rc_api(&mut context, &host_paths, &generation_config.vars)?;
+
+    let parsed = parse_file(&mut context, &loader, js_code, &js_file_path)
+        .map_err(|e| format!("Failed to evaluate JavaScript: {}", e))?;
+    match parsed {
+        ParsedFile::Script(script) => {
+            load_libraries_into_global(&mut context, &loader)?;
+            script
+                .evaluate(&mut context)
+                .map_err(|e| format!("Failed to evaluate JavaScript: {}", e))?;
+            let global = context.global_object();
+            Ok((context, global))
+        }
+        ParsedFile::Module(module) => {
+            let namespace = evaluate_module(&mut context, &module)
+                .map_err(|e| format!("Failed to evaluate JavaScript: {}", e))?;
+            Ok((context, namespace))
+        }
+    }
+}
+
+/// Files besides the parser that its output depends on, for `--watch`: every module it imports,
+/// directly or through other imports, plus the `libs` directories and the files in them.
+/// Imports are resolved and parsed but never evaluated, so no parser code runs. Anything that
+/// cannot be resolved is left out; the next run reports the error.
+pub fn parser_sources(js_code: &str, js_file_str: &str, generation_config: &Config) -> Vec<PathBuf> {
+    let js_file_path = canonical_parser_path(js_file_str);
+    let parser_dir = js_file_path.parent().unwrap_or(Path::new("/"));
+    let lib_dirs = generation_config.libs.clone().unwrap_or_default();
+    let loader = Rc::new(ParserModuleLoader::new(parser_dir, &lib_dirs));
+
+    let mut sources: Vec<PathBuf> = loader.lib_dirs().to_vec();
+    sources.extend(loader.library_files().unwrap_or_default());
+    let Ok(mut context) = Context::builder().module_loader(loader.clone()).build() else {
+        return sources;
+    };
+    if let Ok(ParsedFile::Module(module)) = parse_file(&mut context, &loader, js_code, &js_file_path) {
+        module.load(&mut context);
+        context.run_jobs();
+    }
+    sources.extend(
+        loader
+            .loaded_paths()
+            .into_iter()
+            .filter(|path| *path != js_file_path),
+    );
+    sources.sort();
+    sources.dedup();
+    sources
+}
+
+fn canonical_parser_path(js_file_str: &str) -> PathBuf {
+    fs::canonicalize(js_file_str).unwrap_or_else(|_| PathBuf::from(js_file_str))
+}
+
+/// The directory relative imports, `rc.readFile` and templates resolve against
+pub fn parser_dir(js_file_str: &str) -> PathBuf {
+    let js_file_path = canonical_parser_path(js_file_str);
+    js_file_path.parent().unwrap_or(Path::new("/")).to_path_buf()
+}
+
+fn parse_file(
+    context: &mut Context,
+    loader: &ParserModuleLoader,
+    js_code: &str,
+    js_file_path: &Path,
+) -> Result<ParsedFile, String> {
+    let is_mjs = js_file_path.extension().is_some_and(|ext| ext == "mjs");
+    let script_error = if is_mjs {
+        None
+    } else {
+        match Script::parse(Source::from_bytes(js_code), None, context) {
+            Ok(script) => return Ok(ParsedFile::Script(script)),
+            Err(e) => Some(e),
+        }
+    };
+
+    let source = Source::from_reader(js_code.as_bytes(), Some(js_file_path));
+    // A plain script with a syntax error is reported as such, not as a module error
+    let module = Module::parse(source, None, context)
+        .map_err(|e| script_error.unwrap_or(e).to_string())?;
+    loader.insert(js_file_path.to_path_buf(), module.clone());
+    Ok(ParsedFile::Module(module))
+}
+
+/// Load, link and evaluate a module, returning its namespace object
+fn evaluate_module(context: &mut Context, module: &Module) -> Result<JsObject, String> {
+    let promise = module.load_link_evaluate(context);
+    context.run_jobs();
+    match promise.state() {
+        PromiseState::Fulfilled(_) => Ok(module.namespace(context)),
+        PromiseState::Rejected(reason) => Err(reason.display().to_string()),
+        PromiseState::Pending => Err("module did not finish evaluating".to_string()),
+    }
+}
+
+/// Evaluate every library file before a script parser: plain scripts define their globals
+/// directly, and the exports of module files are copied onto the global object
+fn load_libraries_into_global(context: &mut Context, loader: &ParserModuleLoader) -> Result<(), String> {
+    for lib_file in loader.library_files()? {
+        let display_path = lib_file.display().to_string();
+        let lib_error = |e: String| format!("Failed to load library '{}': {}", display_path, e);
+
+        let lib_code = fs::read_to_string(&lib_file).map_err(|e| lib_error(e.to_string()))?;
+        match parse_file(context, loader, &lib_code, &lib_file).map_err(lib_error)? {
+            ParsedFile::Script(script) => {
+                script
+                    .evaluate(context)
+                    .map_err(|e| lib_error(e.to_string()))?;
+            }
+            ParsedFile::Module(module) => {
+                let namespace = evaluate_module(context, &module).map_err(lib_error)?;
+                let global = context.global_object();
+                let keys = namespace
+                    .own_property_keys(context)
+                    .map_err(|e| lib_error(e.to_string()))?;
+                for key in keys {
+                    if matches!(key, PropertyKey::Symbol(_)) {
+                        continue;
+                    }
+                    let value = namespace
+                        .get(key.clone(), context)
+                        .map_err(|e| lib_error(e.to_string()))?;
+                    global
+                        .set(key, value, false, context)
+                        .map_err(|e| lib_error(e.to_string()))?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
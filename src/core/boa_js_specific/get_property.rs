@@ -1,3 +1,4 @@
+use boa_engine::object::builtins::JsArray;
 use boa_engine::property::PropertyKey;
 use boa_engine::{Context, JsObject, JsString, JsValue};
 
@@ -14,6 +15,42 @@ pub fn get_property(object: &JsObject, name: &str, context: &mut Context) -> Str
     value.to_string(context).unwrap().to_std_string_escaped()
 }
 
+/// A property holding a string or an array of strings, e.g. `fn.after`; anything else is empty
+pub fn get_string_list(object: &JsObject, name: &str, context: &mut Context) -> Vec<String> {
+    let property = object
+        .get(PropertyKey::String(JsString::from(name)), context)
+        .unwrap_or_default();
+    if let Some(text) = property.as_string() {
+        return vec![text.to_std_string_escaped()];
+    }
+    let Some(array) = property.as_object().and_then(|obj| JsArray::from_object(obj.clone()).ok()) else {
+        return Vec::new();
+    };
+    let length = array.length(context).unwrap_or(0);
+    (0..length)
+        .filter_map(|i| array.get(i, context).ok())
+        .filter_map(|item| item.as_string().map(|text| text.to_std_string_escaped()))
+        .collect()
+}
+
+/// A property read as a boolean the JavaScript way, or `default` when it is not set
+pub fn get_flag(object: &JsObject, name: &str, default: bool, context: &mut Context) -> bool {
+    let property = object
+        .get(PropertyKey::String(JsString::from(name)), context)
+        .unwrap_or_default();
+    if property.is_undefined() {
+        default
+    } else {
+        property.to_boolean()
+    }
+}
+
+pub fn has_callable_property(object: &JsObject, name: &str, context: &mut Context) -> bool {
+    object
+        .get(PropertyKey::String(JsString::from(name)), context)
+        .is_ok_and(|property| property.is_callable())
+}
+
 pub fn has_property(object: &JsObject, name: &str, context: &mut Context) -> bool {
     let property = object
         .get(PropertyKey::String(JsString::from(name)), context)
@@ -0,0 +1,240 @@
+use crate::core::boa_js_specific::js_convert_judge::{is_array, js_value_to_string};
+use crate::core::console_print::print_console::print_console_message;
+use crate::core::data_hold::data_struct::ConsoleWarning;
+use boa_engine::gc::{Gc, GcRefCell};
+use boa_engine::object::ObjectInitializer;
+use boa_engine::property::{Attribute, PropertyKey};
+use boa_engine::{js_string, Context, JsObject, JsResult, JsValue, NativeFunction};
+use boa_gc::{Finalize, Trace};
+
+/// Severity of a console call; `warn` and `error` calls are also kept for the run report
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ConsoleLevel {
+    Log,
+    Info,
+    Warn,
+    Error,
+}
+
+impl ConsoleLevel {
+    fn as_str(self) -> &'static str {
+        match self {
+            ConsoleLevel::Log => "log",
+            ConsoleLevel::Info => "info",
+            ConsoleLevel::Warn => "warn",
+            ConsoleLevel::Error => "error",
+        }
+    }
+}
+
+#[derive(Debug, Default, Trace, Finalize)]
+struct ConsoleState {
+    function_name: String,
+    warnings: Vec<(String, String, String)>, // (function name, level, message)
+}
+
+/// The `console` object handed to parsers. Output goes to the tool's log on stderr
+/// (stdout may carry rendered files), prefixed with the function currently running.
+#[derive(Clone)]
+pub struct ParserConsole {
+    state: Gc<GcRefCell<ConsoleState>>,
+}
+
+impl ParserConsole {
+    /// `name` prefixes output until the first function call, e.g. the parser file name
+    pub fn new(name: &str) -> Self {
+        ParserConsole {
+            state: Gc::new(GcRefCell::new(ConsoleState {
+                function_name: name.to_string(),
+                warnings: Vec::new(),
+            })),
+        }
+    }
+
+    /// Install `console` (log/info/warn/error/table) as a global of the context
+    pub fn register(&self, context: &mut Context) -> Result<(), String> {
+        let console = ObjectInitializer::new(context)
+            .function(
+                NativeFunction::from_copy_closure_with_captures(
+                    |_, args, state, context| write_message(ConsoleLevel::Log, args, state, context),
+                    self.state.clone(),
+                ),
+                js_string!("log"),
+                0,
+            )
+            .function(
+                NativeFunction::from_copy_closure_with_captures(
+                    |_, args, state, context| write_message(ConsoleLevel::Info, args, state, context),
+                    self.state.clone(),
+                ),
+                js_string!("info"),
+                0,
+            )
+            .function(
+                NativeFunction::from_copy_closure_with_captures(
+                    |_, args, state, context| write_message(ConsoleLevel::Warn, args, state, context),
+                    self.state.clone(),
+                ),
+                js_string!("warn"),
+                0,
+            )
+            .function(
+                NativeFunction::from_copy_closure_with_captures(
+                    |_, args, state, context| write_message(ConsoleLevel::Error, args, state, context),
+                    self.state.clone(),
+                ),
+                js_string!("error"),
+                0,
+            )
+            .function(
+                NativeFunction::from_copy_closure_with_captures(write_table, self.state.clone()),
+                js_string!("table"),
+                1,
+            )
+            .build();
+
+        context
+            .register_global_property(js_string!("console"), console, Attribute::all())
+            .map_err(|e| format!("Failed to register console: {}", e))
+    }
+
+    /// Name the function whose output follows
+    pub fn set_function(&self, name: &str) {
+        self.state.borrow_mut().function_name = name.to_string();
+    }
+
+    /// Warnings and errors logged so far, oldest first
+    pub fn take_warnings(&self) -> Vec<ConsoleWarning> {
+        self.state
+            .borrow_mut()
+            .warnings
+            .drain(..)
+            .map(|(name, level, message)| ConsoleWarning { name, level, message })
+            .collect()
+    }
+}
+
+fn write_message(
+    level: ConsoleLevel,
+    args: &[JsValue],
+    state: &Gc<GcRefCell<ConsoleState>>,
+    _context: &mut Context,
+) -> JsResult<JsValue> {
+    let message = args
+        .iter()
+        .map(js_value_to_string)
+        .collect::<Vec<_>>()
+        .join(" ");
+    record(level, &message, state);
+    Ok(JsValue::undefined())
+}
+
+fn write_table(
+    _this: &JsValue,
+    args: &[JsValue],
+    state: &Gc<GcRefCell<ConsoleState>>,
+    context: &mut Context,
+) -> JsResult<JsValue> {
+    let data = args.first().cloned().unwrap_or_default();
+    let message = match data.as_object() {
+        Some(object) if !data.is_callable() => format_table(object, &data, context)?,
+        _ => js_value_to_string(&data),
+    };
+    record(ConsoleLevel::Log, &message, state);
+    Ok(JsValue::undefined())
+}
+
+fn record(level: ConsoleLevel, message: &str, state: &Gc<GcRefCell<ConsoleState>>) {
+    let mut state = state.borrow_mut();
+    print_console_message(level.as_str(), &state.function_name, message);
+    if matches!(level, ConsoleLevel::Warn | ConsoleLevel::Error) {
+        let function_name = state.function_name.clone();
+        state
+            .warnings
+            .push((function_name, level.as_str().to_string(), message.to_string()));
+    }
+}
+
+/// Row or column keys of an object: indices for arrays, own string keys otherwise
+fn table_keys(object: &JsObject, value: &JsValue, context: &mut Context) -> JsResult<Vec<PropertyKey>> {
+    let array = is_array(context, value);
+    Ok(object
+        .own_property_keys(context)?
+        .into_iter()
+        .filter(|key| match key {
+            PropertyKey::Index(_) => true,
+            PropertyKey::String(_) => !array,
+            PropertyKey::Symbol(_) => false,
+        })
+        .collect())
+}
+
+fn key_name(key: &PropertyKey) -> String {
+    match key {
+        PropertyKey::String(s) => s.to_std_string_escaped(),
+        PropertyKey::Index(idx) => idx.get().to_string(),
+        PropertyKey::Symbol(_) => String::new(),
+    }
+}
+
+/// Render an array or object as a text table: one row per entry, one column per field of
+/// object entries, and a `Values` column for primitive entries
+fn format_table(object: &JsObject, value: &JsValue, context: &mut Context) -> JsResult<String> {
+    let mut columns: Vec<String> = Vec::new();
+    let mut rows: Vec<(String, Vec<(String, String)>)> = Vec::new();
+    let mut has_values = false;
+
+    for row_key in table_keys(object, value, context)? {
+        let row_value = object.get(row_key.clone(), context)?;
+        let mut cells = Vec::new();
+        match row_value.as_object() {
+            Some(row_object) if !row_value.is_callable() => {
+                for column_key in table_keys(row_object, &row_value, context)? {
+                    let column = key_name(&column_key);
+                    let cell = row_object.get(column_key, context)?;
+                    if !columns.contains(&column) {
+                        columns.push(column.clone());
+                    }
+                    cells.push((column, js_value_to_string(&cell)));
+                }
+            }
+            _ => {
+                has_values = true;
+                cells.push(("Values".to_string(), js_value_to_string(&row_value)));
+            }
+        }
+        rows.push((key_name(&row_key), cells));
+    }
+    if has_values {
+        columns.push("Values".to_string());
+    }
+
+    let mut header = vec!["(index)".to_string()];
+    header.extend(columns.iter().cloned());
+    let mut table = vec![header];
+    for (index, cells) in rows {
+        let mut line = vec![index];
+        for column in &columns {
+            let cell = cells.iter().find(|(name, _)| name == column);
+            line.push(cell.map(|(_, text)| text.clone()).unwrap_or_default());
+        }
+        table.push(line);
+    }
+
+    let widths: Vec<usize> = (0..table[0].len())
+        .map(|i| table.iter().map(|line| line[i].chars().count()).max().unwrap_or(0))
+        .collect();
+    let text = table
+        .iter()
+        .map(|line| {
+            let cells: Vec<String> = line
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                .collect();
+            format!("| {} |", cells.join(" | "))
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    Ok(text)
+}
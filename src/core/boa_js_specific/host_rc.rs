@@ -0,0 +1,356 @@
+use crate::core::data_hold::data_struct::Config;
+use crate::core::fs_handles::sandbox_path::{canonical_roots, resolve_sandboxed_path};
+use crate::core::fs_handles::validate_config::config_syntax_error;
+use crate::core::functions_handles::inflect;
+use crate::core::functions_handles::render_template::render_template;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use boa_engine::object::builtins::JsArray;
+use boa_engine::object::ObjectInitializer;
+use boa_engine::property::Attribute;
+use boa_engine::{js_string, Context, JsArgs, JsNativeError, JsResult, JsString, JsValue, NativeFunction};
+use boa_gc::{Finalize, Trace};
+use md5::Md5;
+use sha2::{Digest, Sha256};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Where the file helpers may read: paths resolve like output paths (alias prefix, otherwise
+/// relative to the parser directory) and must land inside the project root or an alias target
+#[derive(Debug, Clone, Trace, Finalize)]
+pub struct HostPaths {
+    path_alias: HashMap<String, String>,
+    base_dir: PathBuf,
+    allowed_roots: Vec<PathBuf>,
+}
+
+impl HostPaths {
+    /// The project root is the directory of rc.config.json, or the parser directory without one
+    pub fn for_parser(generation_config: &Config, parser_dir: &Path) -> Self {
+        let path_alias = generation_config.alias.clone().unwrap_or_default();
+        let project_root = generation_config
+            .path
+            .as_deref()
+            .and_then(|config_path| Path::new(config_path).parent())
+            .unwrap_or(parser_dir);
+        let roots = std::iter::once(project_root).chain(path_alias.values().map(Path::new));
+        HostPaths {
+            allowed_roots: canonical_roots(roots),
+            base_dir: parser_dir.to_path_buf(),
+            path_alias,
+        }
+    }
+
+    fn resolve(&self, function: &str, requested: &str) -> JsResult<PathBuf> {
+        resolve_sandboxed_path(requested, &self.path_alias, &self.base_dir, &self.allowed_roots)
+            .map_err(|e| JsNativeError::typ().with_message(format!("{}: {}", function, e)).into())
+    }
+
+    /// A template or partial: its resolved path for error messages, and its source
+    fn read_template(&self, requested: &str) -> Result<(String, String), String> {
+        let path = resolve_sandboxed_path(requested, &self.path_alias, &self.base_dir, &self.allowed_roots)?;
+        let source = fs::read_to_string(&path)
+            .map_err(|e| format!("cannot read template '{}': {}", path.display(), e))?;
+        Ok((path.display().to_string(), source))
+    }
+
+    /// Render the template at `requested`; partials resolve the same way as the template
+    pub fn render_template_file(&self, requested: &str, data: &Value) -> Result<String, String> {
+        let (name, source) = self.read_template(requested)?;
+        render_template(&source, &name, data, &mut |partial| self.read_template(partial))
+    }
+}
+
+/// A function result of the form `{template: "@/tpl/X.hbs", data: {...}}`, rendered like
+/// `rc.render(template, data)`. `None` when the result has no string `template` property.
+pub fn template_result(
+    context: &mut Context,
+    result: &JsValue,
+    host_paths: &HostPaths,
+) -> Result<Option<String>, String> {
+    let Some(object) = result.as_object().filter(|object| !object.is_callable() && !object.is_array())
+    else {
+        return Ok(None);
+    };
+    let template = object
+        .get(js_string!("template"), context)
+        .map_err(|e| format!("Failed to get template: {}", e))?;
+    let Some(template) = template.as_string().map(|t| t.to_std_string_escaped()) else {
+        return Ok(None);
+    };
+    let data = object
+        .get(js_string!("data"), context)
+        .map_err(|e| format!("Failed to get template data: {}", e))?;
+    let data = template_data(&data, context).map_err(|e| format!("Failed to convert template data: {}", e))?;
+    host_paths.render_template_file(&template, &data).map(Some)
+}
+
+/// Template data as JSON; a missing `data` renders against an empty object
+fn template_data(data: &JsValue, context: &mut Context) -> JsResult<Value> {
+    if data.is_undefined() {
+        Ok(Value::Object(Map::new()))
+    } else {
+        data.to_json(context)
+    }
+}
+
+/// Install the global `rc` object: Rust-backed helpers available to every parser,
+/// plus `rc.vars` holding the `--vars`/`--define` variables
+pub fn register_rc_api(
+    context: &mut Context,
+    host_paths: &HostPaths,
+    vars: &Map<String, Value>,
+) -> Result<(), String> {
+    let vars = JsValue::from_json(&Value::Object(vars.clone()), context)
+        .map_err(|e| format!("Failed to convert vars: {}", e))?;
+    let read_file = NativeFunction::from_copy_closure_with_captures(rc_read_file, host_paths.clone());
+    let read_json = NativeFunction::from_copy_closure_with_captures(rc_read_json, host_paths.clone());
+    let list_dir = NativeFunction::from_copy_closure_with_captures(rc_list_dir, host_paths.clone());
+    let render = NativeFunction::from_copy_closure_with_captures(rc_render, host_paths.clone());
+    let case = ObjectInitializer::new(context)
+        .function(NativeFunction::from_fn_ptr(rc_case_snake), js_string!("snake"), 1)
+        .function(NativeFunction::from_fn_ptr(rc_case_camel), js_string!("camel"), 1)
+        .function(NativeFunction::from_fn_ptr(rc_case_pascal), js_string!("pascal"), 1)
+        .function(NativeFunction::from_fn_ptr(rc_case_kebab), js_string!("kebab"), 1)
+        .function(NativeFunction::from_fn_ptr(rc_case_constant), js_string!("constant"), 1)
+        .build();
+    let rc = ObjectInitializer::new(context)
+        .function(NativeFunction::from_fn_ptr(rc_uuid), js_string!("uuid"), 0)
+        .function(NativeFunction::from_fn_ptr(rc_sha256), js_string!("sha256"), 1)
+        .function(NativeFunction::from_fn_ptr(rc_md5), js_string!("md5"), 1)
+        .function(NativeFunction::from_fn_ptr(rc_base64_encode), js_string!("base64Encode"), 1)
+        .function(NativeFunction::from_fn_ptr(rc_base64_decode), js_string!("base64Decode"), 1)
+        .function(NativeFunction::from_fn_ptr(rc_hash), js_string!("hash"), 1)
+        .function(read_file, js_string!("readFile"), 1)
+        .function(read_json, js_string!("readJson"), 1)
+        .function(list_dir, js_string!("listDir"), 1)
+        .function(render, js_string!("render"), 2)
+        .function(NativeFunction::from_fn_ptr(rc_plural), js_string!("plural"), 1)
+        .function(NativeFunction::from_fn_ptr(rc_singular), js_string!("singular"), 1)
+        .function(NativeFunction::from_fn_ptr(rc_humanize), js_string!("humanize"), 1)
+        .property(js_string!("case"), case, Attribute::all())
+        .property(js_string!("vars"), vars, Attribute::all())
+        .build();
+
+    context
+        .register_global_property(js_string!("rc"), rc, Attribute::all())
+        .map_err(|e| format!("Failed to register rc: {}", e))
+}
+
+/// The first argument converted to a string the JavaScript way
+fn string_arg(args: &[JsValue], context: &mut Context) -> JsResult<String> {
+    Ok(args.get_or_undefined(0).to_string(context)?.to_std_string_escaped())
+}
+
+/// `rc.uuid()`: a random v4 UUID
+fn rc_uuid(_: &JsValue, _: &[JsValue], _: &mut Context) -> JsResult<JsValue> {
+    Ok(JsString::from(Uuid::new_v4().to_string()).into())
+}
+
+/// `rc.sha256(text)`: lowercase hex digest of the UTF-8 bytes
+fn rc_sha256(_: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let text = string_arg(args, context)?;
+    Ok(JsString::from(format!("{:x}", Sha256::digest(text.as_bytes()))).into())
+}
+
+/// `rc.md5(text)`: lowercase hex digest of the UTF-8 bytes
+fn rc_md5(_: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let text = string_arg(args, context)?;
+    Ok(JsString::from(format!("{:x}", Md5::digest(text.as_bytes()))).into())
+}
+
+/// `rc.base64Encode(text)`: standard, padded base64 of the UTF-8 bytes
+fn rc_base64_encode(_: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let text = string_arg(args, context)?;
+    Ok(JsString::from(BASE64.encode(text.as_bytes())).into())
+}
+
+/// `rc.base64Decode(text)`: the decoded bytes as a UTF-8 string
+fn rc_base64_decode(_: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let text = string_arg(args, context)?;
+    let bytes = BASE64
+        .decode(text.trim())
+        .map_err(|e| JsNativeError::typ().with_message(format!("rc.base64Decode: {}", e)))?;
+    let decoded = String::from_utf8(bytes).map_err(|_| {
+        JsNativeError::typ().with_message("rc.base64Decode: decoded bytes are not valid UTF-8")
+    })?;
+    Ok(JsString::from(decoded).into())
+}
+
+/// `rc.hash(value)`: 16 hex digits of a 64-bit FNV-1a hash, identical across runs and
+/// platforms. Strings are hashed as-is; other values as JSON with sorted keys, so
+/// `{a, b}` and `{b, a}` give the same id.
+fn rc_hash(_: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let value = args.get_or_undefined(0);
+    let text = match value.as_string() {
+        Some(text) => text.to_std_string_escaped(),
+        None if value.is_undefined() => "undefined".to_string(),
+        None => canonical_json(value.to_json(context)?).to_string(),
+    };
+    Ok(JsString::from(format!("{:016x}", fnv1a_64(text.as_bytes()))).into())
+}
+
+/// The same JSON with every object's keys sorted, so hashes do not depend on insertion order
+fn canonical_json(value: Value) -> Value {
+    match value {
+        Value::Object(object) => {
+            let mut entries: Vec<(String, Value)> = object.into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            Value::Object(entries.into_iter().map(|(key, value)| (key, canonical_json(value))).collect())
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(canonical_json).collect()),
+        other => other,
+    }
+}
+
+fn fnv1a_64(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+    bytes.iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+    })
+}
+
+/// Apply a Rust string conversion to the first argument
+fn convert_string(
+    args: &[JsValue],
+    context: &mut Context,
+    convert: fn(&str) -> String,
+) -> JsResult<JsValue> {
+    let text = string_arg(args, context)?;
+    Ok(JsString::from(convert(&text)).into())
+}
+
+/// `rc.case.snake("StudentList")`: `student_list`
+fn rc_case_snake(_: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    convert_string(args, context, inflect::snake_case)
+}
+
+/// `rc.case.camel("student-list")`: `studentList`
+fn rc_case_camel(_: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    convert_string(args, context, inflect::camel_case)
+}
+
+/// `rc.case.pascal("student_list")`: `StudentList`
+fn rc_case_pascal(_: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    convert_string(args, context, inflect::pascal_case)
+}
+
+/// `rc.case.kebab("StudentList")`: `student-list`
+fn rc_case_kebab(_: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    convert_string(args, context, inflect::kebab_case)
+}
+
+/// `rc.case.constant("studentList")`: `STUDENT_LIST`
+fn rc_case_constant(_: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    convert_string(args, context, inflect::constant_case)
+}
+
+/// `rc.plural("Category")`: `Categories`; only the last word changes
+fn rc_plural(_: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    convert_string(args, context, inflect::plural)
+}
+
+/// `rc.singular("people")`: `person`; only the last word changes
+fn rc_singular(_: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    convert_string(args, context, inflect::singular)
+}
+
+/// `rc.humanize("studentList")`: `Student list`
+fn rc_humanize(_: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    convert_string(args, context, inflect::humanize)
+}
+
+/// `rc.readFile(path)`: the file's content as a UTF-8 string
+fn rc_read_file(
+    _: &JsValue,
+    args: &[JsValue],
+    host_paths: &HostPaths,
+    context: &mut Context,
+) -> JsResult<JsValue> {
+    let path = host_paths.resolve("rc.readFile", &string_arg(args, context)?)?;
+    let text = fs::read_to_string(&path).map_err(|e| {
+        let message = format!("rc.readFile: cannot read '{}': {}", path.display(), e);
+        JsNativeError::typ().with_message(message)
+    })?;
+    Ok(JsString::from(text).into())
+}
+
+/// `rc.readJson(path)`: the file parsed as JSON; syntax errors report `file:line:column`
+fn rc_read_json(
+    _: &JsValue,
+    args: &[JsValue],
+    host_paths: &HostPaths,
+    context: &mut Context,
+) -> JsResult<JsValue> {
+    let path = host_paths.resolve("rc.readJson", &string_arg(args, context)?)?;
+    let text = fs::read_to_string(&path).map_err(|e| {
+        let message = format!("rc.readJson: cannot read '{}': {}", path.display(), e);
+        JsNativeError::typ().with_message(message)
+    })?;
+    let json: serde_json::Value = serde_json::from_str(&text).map_err(|e| {
+        let location = config_syntax_error(&path.display().to_string(), &e);
+        JsNativeError::syntax().with_message(format!("rc.readJson: {}", location))
+    })?;
+    JsValue::from_json(&json, context)
+}
+
+/// `rc.listDir(path)`: sorted entry names, with a trailing `/` on directories
+fn rc_list_dir(
+    _: &JsValue,
+    args: &[JsValue],
+    host_paths: &HostPaths,
+    context: &mut Context,
+) -> JsResult<JsValue> {
+    let path = host_paths.resolve("rc.listDir", &string_arg(args, context)?)?;
+    let entries = fs::read_dir(&path).map_err(|e| {
+        let message = format!("rc.listDir: cannot list '{}': {}", path.display(), e);
+        JsNativeError::typ().with_message(message)
+    })?;
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if entry.path().is_dir() {
+                format!("{}/", name)
+            } else {
+                name
+            }
+        })
+        .collect();
+    names.sort();
+    let names = names.into_iter().map(|name| JsString::from(name).into());
+    Ok(JsArray::from_iter(names, context).into())
+}
+
+/// `rc.render(templatePath, data)`: the template rendered with `data`, as a string
+fn rc_render(
+    _: &JsValue,
+    args: &[JsValue],
+    host_paths: &HostPaths,
+    context: &mut Context,
+) -> JsResult<JsValue> {
+    let template = string_arg(args, context)?;
+    let data = template_data(args.get_or_undefined(1), context)?;
+    let rendered = host_paths
+        .render_template_file(&template, &data)
+        .map_err(|e| JsNativeError::typ().with_message(format!("rc.render: {}", e)))?;
+    Ok(JsString::from(rendered).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn canonical_json_sorts_keys_recursively() {
+        let a = canonical_json(json!({"b": 1, "a": {"y": [{"d": 1, "c": 2}], "x": 0}}));
+        let b = canonical_json(json!({"a": {"x": 0, "y": [{"c": 2, "d": 1}]}, "b": 1}));
+        assert_eq!(a.to_string(), r#"{"a":{"x":0,"y":[{"c":2,"d":1}]},"b":1}"#);
+        assert_eq!(a.to_string(), b.to_string());
+    }
+}
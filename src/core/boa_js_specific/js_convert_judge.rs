@@ -1,4 +1,9 @@
-use boa_engine::{Context, JsString, JsValue};
+use crate::core::data_hold::data_struct::FileContent;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use boa_engine::object::builtins::{JsArrayBuffer, JsUint8Array};
+use boa_engine::property::PropertyKey;
+use boa_engine::{js_string, Context, JsString, JsValue};
 
 /// Check if a JsValue is an array using Array.isArray()
 pub(crate) fn is_array(context: &mut Context, value: &JsValue) -> bool {
@@ -23,7 +28,7 @@ pub(crate) fn is_array(context: &mut Context, value: &JsValue) -> bool {
     // Call Array.isArray(value)
     match is_array_fn.as_callable() {
         Some(callable) => {
-            let result = callable.call(&JsValue::undefined(), &[value.clone()], context);
+            let result = callable.call(&JsValue::undefined(), std::slice::from_ref(value), context);
             match result {
                 Ok(res) => res.as_boolean().unwrap_or(false),
                 Err(_) => false,
@@ -90,4 +95,54 @@ pub(crate) fn extract_string(value: &JsValue) -> String {
     } else {
         value.display().to_string()
     }
-}
\ No newline at end of file
+}
+
+/// Raw bytes of a `Uint8Array`, an `ArrayBuffer` or a `{base64: "..."}` wrapper (an object whose
+/// only key is `base64`). `None` for any other value.
+pub(crate) fn binary_content(context: &mut Context, value: &JsValue) -> Result<Option<Vec<u8>>, String> {
+    let Some(object) = value.as_object() else {
+        return Ok(None);
+    };
+
+    if let Ok(array) = JsUint8Array::from_object(object.clone()) {
+        let offset = array.byte_offset(context).map_err(|e| format!("Failed to read Uint8Array: {}", e))?;
+        let length = array.byte_length(context).map_err(|e| format!("Failed to read Uint8Array: {}", e))?;
+        let buffer = array.buffer(context).map_err(|e| format!("Failed to read Uint8Array: {}", e))?;
+        let bytes = buffer
+            .as_object()
+            .and_then(|buffer| JsArrayBuffer::from_object(buffer.clone()).ok())
+            .and_then(|buffer| buffer.data().map(|data| data[offset..offset + length].to_vec()))
+            .ok_or("Uint8Array has a detached buffer")?;
+        return Ok(Some(bytes));
+    }
+
+    if let Ok(buffer) = JsArrayBuffer::from_object(object.clone()) {
+        let bytes = buffer.data().map(|data| data.to_vec()).ok_or("ArrayBuffer is detached")?;
+        return Ok(Some(bytes));
+    }
+
+    let keys = object
+        .own_property_keys(context)
+        .map_err(|e| format!("Failed to get keys: {}", e))?;
+    if keys.len() != 1 || keys[0] != PropertyKey::from(js_string!("base64")) {
+        return Ok(None);
+    }
+    let encoded = object
+        .get(js_string!("base64"), context)
+        .map_err(|e| format!("Failed to get base64: {}", e))?;
+    let Some(encoded) = encoded.as_string().map(|s| s.to_std_string_escaped()) else {
+        return Ok(None);
+    };
+    BASE64
+        .decode(encoded.trim())
+        .map(Some)
+        .map_err(|e| format!("Invalid base64 content: {}", e))
+}
+
+/// File content from a value: raw bytes for binary values, otherwise `extract_string`
+pub(crate) fn extract_file_content(context: &mut Context, value: &JsValue) -> Result<FileContent, String> {
+    Ok(match binary_content(context, value)? {
+        Some(bytes) => FileContent::Binary(bytes),
+        None => FileContent::Text(extract_string(value)),
+    })
+}
@@ -2,3 +2,8 @@ pub mod built_in_filter;
 pub mod get_property;
 pub mod js_convert_judge;
 pub mod call_func;
+pub mod evaluate_parser;
+pub mod host_console;
+pub mod host_rc;
+pub mod parser_module_loader;
+pub mod settle_promise;
\ No newline at end of file
@@ -0,0 +1,139 @@
+use crate::core::fs_handles::normalize_path::normalize_path;
+use boa_engine::module::{ModuleLoader, Referrer};
+use boa_engine::{Context, JsNativeError, JsResult, JsString, Module, Source};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Extensions tried, in order, when a bare import names a file without one
+const MODULE_EXTENSIONS: [&str; 2] = ["js", "mjs"];
+
+/// Module loader for parsers: relative imports resolve against the importing file and must stay
+/// inside the parser directory or a library directory; bare names (`import "strings"`) are looked
+/// up in the parser directory, then in each `libs` directory in order.
+pub struct ParserModuleLoader {
+    parser_dir: PathBuf,
+    lib_dirs: Vec<PathBuf>,
+    modules: RefCell<HashMap<PathBuf, Module>>,
+}
+
+impl ParserModuleLoader {
+    pub fn new(parser_dir: &Path, lib_dirs: &[String]) -> Self {
+        let canonical = |dir: &Path| fs::canonicalize(dir).unwrap_or_else(|_| normalize_path(dir));
+        ParserModuleLoader {
+            parser_dir: canonical(parser_dir),
+            lib_dirs: lib_dirs.iter().map(|dir| canonical(Path::new(dir))).collect(),
+            modules: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Register an already parsed module (the parser itself) so imports of it reuse it
+    pub fn insert(&self, path: PathBuf, module: Module) {
+        self.modules.borrow_mut().insert(path, module);
+    }
+
+    /// The module at `path`, parsed on first use and shared by every later import of it
+    pub fn load_module(&self, path: &Path, context: &mut Context) -> JsResult<Module> {
+        if let Some(module) = self.modules.borrow().get(path) {
+            return Ok(module.clone());
+        }
+        let source = Source::from_filepath(path).map_err(|e| {
+            JsNativeError::typ().with_message(format!("could not open '{}': {}", path.display(), e))
+        })?;
+        let module = Module::parse(source, None, context)?;
+        self.insert(path.to_path_buf(), module.clone());
+        Ok(module)
+    }
+
+    /// Every module file parsed so far: the parser itself, its imports and their imports
+    pub fn loaded_paths(&self) -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = self.modules.borrow().keys().cloned().collect();
+        paths.sort();
+        paths
+    }
+
+    /// The canonical `libs` directories
+    pub fn lib_dirs(&self) -> &[PathBuf] {
+        &self.lib_dirs
+    }
+
+    /// Every `.js`/`.mjs` file directly inside the library directories, in config then name order
+    pub fn library_files(&self) -> Result<Vec<PathBuf>, String> {
+        let mut files = Vec::new();
+        for lib_dir in &self.lib_dirs {
+            let entries = fs::read_dir(lib_dir)
+                .map_err(|e| format!("Cannot read library directory '{}': {}", lib_dir.display(), e))?;
+            let mut lib_files: Vec<PathBuf> = entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| {
+                    path.is_file()
+                        && path
+                            .extension()
+                            .is_some_and(|ext| MODULE_EXTENSIONS.iter().any(|known| ext == *known))
+                })
+                .collect();
+            lib_files.sort();
+            files.extend(lib_files);
+        }
+        Ok(files)
+    }
+
+    fn resolve(&self, specifier: &str, referrer: Option<&Path>) -> Result<PathBuf, String> {
+        if specifier.starts_with("./") || specifier.starts_with("../") {
+            let referrer_dir = referrer
+                .and_then(|path| path.parent())
+                .ok_or_else(|| format!("cannot resolve '{}' without an importing file", specifier))?;
+            let path = normalize_path(&referrer_dir.join(specifier));
+            let allowed = std::iter::once(&self.parser_dir)
+                .chain(&self.lib_dirs)
+                .any(|root| path.starts_with(root));
+            if !allowed {
+                return Err(format!(
+                    "'{}' is outside the parser directory and the configured libs",
+                    specifier
+                ));
+            }
+            return Ok(path);
+        }
+
+        if Path::new(specifier).is_absolute() {
+            return Err(format!("absolute import '{}' is not allowed", specifier));
+        }
+
+        for root in std::iter::once(&self.parser_dir).chain(&self.lib_dirs) {
+            let path = normalize_path(&root.join(specifier));
+            if !path.starts_with(root) {
+                continue;
+            }
+            let candidates = std::iter::once(path.clone()).chain(
+                MODULE_EXTENSIONS
+                    .iter()
+                    .map(|ext| PathBuf::from(format!("{}.{}", path.display(), ext))),
+            );
+            for candidate in candidates {
+                if candidate.is_file() {
+                    return Ok(candidate);
+                }
+            }
+        }
+        Err(format!("cannot find module '{}' in the parser directory or the configured libs", specifier))
+    }
+}
+
+impl ModuleLoader for ParserModuleLoader {
+    fn load_imported_module(
+        &self,
+        referrer: Referrer,
+        specifier: JsString,
+        finish_load: Box<dyn FnOnce(JsResult<Module>, &mut Context)>,
+        context: &mut Context,
+    ) {
+        let result = self
+            .resolve(&specifier.to_std_string_escaped(), referrer.path())
+            .map_err(|e| JsNativeError::typ().with_message(e).into())
+            .and_then(|path| self.load_module(&path, context));
+
+        finish_load(result, context);
+    }
+}
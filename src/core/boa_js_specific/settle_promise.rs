@@ -0,0 +1,20 @@
+use boa_engine::builtins::promise::PromiseState;
+use boa_engine::object::builtins::JsPromise;
+use boa_engine::{Context, JsError, JsValue};
+
+/// Await a function result: a Promise (from an `async` function or returned directly) is
+/// driven through the job queue until it settles; any other value is returned as-is.
+/// A rejection becomes an error carrying the reason, formatted like a thrown error.
+pub fn settle_promise(context: &mut Context, result: JsValue) -> Result<JsValue, String> {
+    let Some(promise) = result.as_object().and_then(|object| JsPromise::from_object(object.clone()).ok())
+    else {
+        return Ok(result);
+    };
+
+    context.run_jobs();
+    match promise.state() {
+        PromiseState::Fulfilled(value) => Ok(value),
+        PromiseState::Rejected(reason) => Err(JsError::from_opaque(reason).to_string()),
+        PromiseState::Pending => Err("the returned promise never settled".to_string()),
+    }
+}
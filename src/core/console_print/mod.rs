@@ -1 +1,2 @@
-pub mod print_console;
\ No newline at end of file
+pub mod print_console;
+pub mod run_report;
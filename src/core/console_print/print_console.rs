@@ -1,11 +1,192 @@
+use crate::core::data_hold::data_struct::{
+    CheckResult, FailureResult, FunctionForExecute, RenderedFile, SkipReason, SuccessResult,
+};
+use std::io::{self, Write};
+
+/// Forward a parser's console output to stderr, each line prefixed with the running function
+pub fn print_console_message(level: &str, function_name: &str, message: &str) {
+    let marker = match level {
+        "warn" => "⚠️",
+        "error" => "🛑",
+        _ => "💬",
+    };
+    for line in message.lines() {
+        eprintln!(" {} [{}] {}", marker, function_name, line);
+    }
+}
+
+/// A function that did not run, on stderr next to the parser's console output
+pub fn print_skipped(function_name: &str, reason: SkipReason) {
+    eprintln!(" ⏭️ [{}] skipped: {}", function_name, reason.describe());
+}
+
 pub fn print_js_required() {
     eprintln!("Error: JavaScript file path required");
-    eprintln!("Usage: js_evaluator --file <JS_FILE> --content <CONTENT_FILE>");
-    eprintln!("   or: js_evaluator <JS_FILE> <CONTENT_FILE>");
+    print_usage();
 }
 
 pub fn print_content_required() {
     eprintln!("Error: Content file path required");
-    eprintln!("Usage: js_evaluator --file <JS_FILE> --content <CONTENT_FILE>");
-    eprintln!("   or: js_evaluator <JS_FILE> <CONTENT_FILE>");
+    print_usage();
+}
+
+fn print_usage() {
+    eprintln!("Usage: js_evaluator [run|check|clean] --file <JS_FILE> --content <CONTENT_FILE>");
+    eprintln!("   or: js_evaluator [run|check|clean] <JS_FILE> <CONTENT_FILE>");
+    eprintln!("  see: js_evaluator --help for init, list and jobs");
+}
+
+pub fn print_dry_run_plan(rendered: &[RenderedFile], failures: &[FailureResult]) {
+    println!(" 📝 Planned file(s) (dry run, nothing written):\n");
+    for file in rendered {
+        println!(
+            "\t{} ({} bytes) from function {}",
+            file.path,
+            file.content.len(),
+            file.name
+        );
+    }
+    if !failures.is_empty() {
+        println!("\n ❌ Errored function(s):\n");
+        for fail in failures {
+            println!(" function name: {} with path {} and error {}", fail.name, fail.path, fail.error);
+        }
+    }
+}
+
+pub fn print_check_report(checked: &[CheckResult], failures: &[FailureResult]) {
+    let outdated: Vec<&CheckResult> = checked.iter().filter(|c| c.diff.is_some()).collect();
+    for check in &outdated {
+        println!("{}", check.diff.as_deref().unwrap_or_default());
+    }
+    println!(
+        " 🔍 Checked {} file(s): {} up to date, {} out of date",
+        checked.len(),
+        checked.len() - outdated.len(),
+        outdated.len()
+    );
+    for check in &outdated {
+        println!("\t{} from function {}", check.path, check.name);
+    }
+    if !failures.is_empty() {
+        println!("\n ❌ Errored function(s):\n");
+        for fail in failures {
+            println!(" function name: {} with path {} and error {}", fail.name, fail.path, fail.error);
+        }
+    }
+}
+
+pub fn print_watch_report(
+    run_count: usize,
+    result: &Result<(Vec<SuccessResult>, Vec<FailureResult>), String>,
+) {
+    match result {
+        Ok((successes, failures)) => {
+            println!(
+                " 🔁 Run #{}: {} file(s) written, {} failure(s)",
+                run_count,
+                successes.len(),
+                failures.len()
+            );
+            for fail in failures {
+                println!("\t❌ {} ({}): {}", fail.name, fail.path, fail.error);
+            }
+        }
+        Err(e) => println!(" 🔁 Run #{}: ❌ {}", run_count, e),
+    }
+}
+
+pub fn print_function_list(exported: &[FunctionForExecute], composed: &[FunctionForExecute]) {
+    println!(" 📄 Path function(s):\n");
+    for func in exported {
+        let path = if func.computed_path { "(computed)" } else { func.path.as_str() };
+        println!("\t{} -> {}{}", func.function_name, path, function_notes(func));
+        print_description(func);
+    }
+    println!("\n 🧩 Compose function(s):\n");
+    for func in composed {
+        println!("\t{}{}", func.function_name, function_notes(func));
+        print_description(func);
+    }
+}
+
+/// ` [react, ui] (after a, b) (disabled)` from `fn.tags`, `fn.after` and `fn.enabled`
+fn function_notes(func: &FunctionForExecute) -> String {
+    let mut notes = String::new();
+    if !func.tags.is_empty() {
+        notes.push_str(&format!(" [{}]", func.tags.join(", ")));
+    }
+    if !func.after.is_empty() {
+        notes.push_str(&format!(" (after {})", func.after.join(", ")));
+    }
+    if !func.enabled {
+        notes.push_str(" (disabled)");
+    }
+    if func.conditional {
+        notes.push_str(" (when)");
+    }
+    notes
+}
+
+fn print_description(func: &FunctionForExecute) {
+    if let Some(description) = &func.description {
+        println!("\t    {}", description);
+    }
+}
+
+pub fn print_clean_report(removed: &[SuccessResult], failures: &[FailureResult]) {
+    println!(" 🧹 Removed {} file(s):\n", removed.len());
+    for file in removed {
+        println!("\t{} from function {}", file.path, file.name);
+    }
+    if !failures.is_empty() {
+        println!("\n ❌ Errored function(s):\n");
+        for fail in failures {
+            println!(" function name: {} with path {} and error {}", fail.name, fail.path, fail.error);
+        }
+    }
+}
+
+pub fn print_init_report(files: &[(String, bool)]) {
+    for (path, written) in files {
+        if *written {
+            println!(" ✨ Created {}", path);
+        } else {
+            println!(" ⏭️ Skipped {} (already exists, use --force to overwrite)", path);
+        }
+    }
+}
+
+/// Print rendered files to stdout: a single file as its raw content,
+/// several files each preceded by a `==> path <==` header
+pub fn print_rendered_to_stdout(rendered: &[RenderedFile]) -> io::Result<()> {
+    let mut stdout = io::stdout().lock();
+    if let [single] = rendered {
+        stdout.write_all(single.content.as_bytes())?;
+        return stdout.flush();
+    }
+    for (index, file) in rendered.iter().enumerate() {
+        if index > 0 {
+            writeln!(stdout)?;
+        }
+        writeln!(stdout, "==> {} <==", file.path)?;
+        stdout.write_all(file.content.as_bytes())?;
+        if !file.content.as_bytes().ends_with(b"\n") {
+            writeln!(stdout)?;
+        }
+    }
+    stdout.flush()
+}
+
+pub fn print_failures_to_stderr(failures: &[FailureResult]) {
+    for fail in failures {
+        eprintln!("❌ function name: {} with path {} and error {}", fail.name, fail.path, fail.error);
+    }
+}
+
+pub fn print_config_source(config_path: Option<&str>) {
+    match config_path {
+        Some(path) => println!(" ⚙️ Config: {}\n", path),
+        None => println!(" ⚙️ Config: no rc.config.json found, aliases are not applied\n"),
+    }
 }
@@ -0,0 +1,190 @@
+use crate::core::data_hold::data_struct::{
+    CheckResult, FailureResult, FileStatus, ReportFormat, RunReport, SuccessResult,
+};
+use std::fs;
+use std::path::Path;
+
+/// Serialize the run report in the requested format and write it to `file_path`
+pub fn write_report(format: ReportFormat, file_path: &str, report: &RunReport) -> Result<(), String> {
+    let serialized = match format {
+        ReportFormat::Json => serde_json::to_string_pretty(report)
+            .map_err(|e| format!("Failed to serialize report: {}", e))?,
+        ReportFormat::Junit => junit_report(report),
+    };
+    fs::write(file_path, serialized)
+        .map_err(|e| format!("Failed to write report '{}': {}", file_path, e))
+}
+
+/// A failure for a parser that could not be read or evaluated, named after the parser file,
+/// so a report of a run that never got to its functions does not read as a clean one
+pub fn parser_failure(js_file_str: &str, error: &str) -> FailureResult {
+    let name = Path::new(js_file_str)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| js_file_str.to_string());
+    FailureResult {
+        name,
+        error: error.to_string(),
+        path: js_file_str.to_string(),
+        diff: None,
+    }
+}
+
+/// Add the outcome of `check` to the report: up-to-date files as unchanged successes, outdated
+/// files as failures carrying their diff
+pub fn add_check_results(report: &mut RunReport, checked: Vec<CheckResult>) {
+    for check in checked {
+        match check.diff {
+            None => report.successes.push(SuccessResult {
+                name: check.name,
+                path: check.path,
+                bytes: check.bytes,
+                status: FileStatus::Unchanged,
+            }),
+            Some(diff) => report.failures.push(FailureResult {
+                name: check.name,
+                error: "out of date".to_string(),
+                path: check.path,
+                diff: Some(diff),
+            }),
+        }
+    }
+}
+
+/// One `<testcase>` per written file, per failure and per skipped function, grouped by
+/// function name; a `check` diff is the body of its `<failure>`, console warnings go to the
+/// suite's `<system-err>`
+fn junit_report(report: &RunReport) -> String {
+    let tests = report.successes.len() + report.failures.len() + report.skipped.len();
+    let failures = report.failures.len();
+    let skipped = report.skipped.len();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"js_evaluator\" tests=\"{}\" failures=\"{}\" skipped=\"{}\">\n",
+        tests, failures, skipped
+    ));
+    xml.push_str(&format!(
+        "  <testsuite name=\"js_evaluator\" tests=\"{}\" failures=\"{}\" skipped=\"{}\">\n",
+        tests, failures, skipped
+    ));
+    if let Some(config) = &report.config {
+        xml.push_str(&format!(
+            "    <properties>\n      <property name=\"config\" value=\"{}\"/>\n    </properties>\n",
+            escape_xml(config)
+        ));
+    }
+    for success in &report.successes {
+        let status = match success.status {
+            FileStatus::Created => "created",
+            FileStatus::Updated => "updated",
+            FileStatus::Unchanged => "unchanged",
+            FileStatus::Removed => "removed",
+        };
+        xml.push_str(&format!(
+            "    <testcase classname=\"{}\" name=\"{}\">\n      <system-out>{} ({} bytes)</system-out>\n    </testcase>\n",
+            escape_xml(&success.name),
+            escape_xml(&success.path),
+            status,
+            success.bytes
+        ));
+    }
+    for fail in &report.failures {
+        xml.push_str(&format!(
+            "    <testcase classname=\"{}\" name=\"{}\">\n      <failure message=\"{}\">{}</failure>\n    </testcase>\n",
+            escape_xml(&fail.name),
+            // A compose function can fail before it names any file
+            escape_xml(if fail.path.is_empty() { &fail.name } else { &fail.path }),
+            escape_xml(&fail.error),
+            escape_xml(fail.diff.as_deref().unwrap_or(&fail.error))
+        ));
+    }
+    for skip in &report.skipped {
+        xml.push_str(&format!(
+            "    <testcase classname=\"{}\" name=\"{}\">\n      <skipped message=\"{}\"/>\n    </testcase>\n",
+            escape_xml(&skip.name),
+            escape_xml(&skip.name),
+            escape_xml(skip.reason.describe())
+        ));
+    }
+    if !report.warnings.is_empty() {
+        let lines: Vec<String> = report
+            .warnings
+            .iter()
+            .map(|warning| format!("[{}] {}: {}", warning.name, warning.level, warning.message))
+            .collect();
+        xml.push_str(&format!(
+            "    <system-err>{}</system-err>\n",
+            escape_xml(&lines.join("\n"))
+        ));
+    }
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failures_without_a_path_are_named_after_the_function() {
+        let report = RunReport {
+            failures: vec![FailureResult {
+                name: "react_make".to_string(),
+                error: "boom".to_string(),
+                path: String::new(),
+                diff: None,
+            }],
+            ..RunReport::default()
+        };
+        let xml = junit_report(&report);
+        assert!(xml.contains("<testcase classname=\"react_make\" name=\"react_make\">"), "{}", xml);
+    }
+
+    #[test]
+    fn parser_failures_count_as_failed_tests() {
+        let report = RunReport {
+            failures: vec![parser_failure("/work/bad.js", "SyntaxError: unexpected token")],
+            ..RunReport::default()
+        };
+        let xml = junit_report(&report);
+        assert!(xml.contains("tests=\"1\" failures=\"1\""), "{}", xml);
+        assert!(xml.contains("<testcase classname=\"bad.js\" name=\"/work/bad.js\">"), "{}", xml);
+        assert!(xml.contains("SyntaxError: unexpected token"), "{}", xml);
+    }
+
+    #[test]
+    fn outdated_files_are_failures_carrying_the_diff() {
+        let mut report = RunReport::default();
+        add_check_results(
+            &mut report,
+            vec![
+                CheckResult {
+                    name: "constants".to_string(),
+                    path: "out/A.java".to_string(),
+                    bytes: 12,
+                    diff: None,
+                },
+                CheckResult {
+                    name: "constants".to_string(),
+                    path: "out/B.java".to_string(),
+                    bytes: 7,
+                    diff: Some("-old\n+new\n".to_string()),
+                },
+            ],
+        );
+        assert_eq!(report.successes.len(), 1);
+        assert_eq!(report.successes[0].status, FileStatus::Unchanged);
+        let xml = junit_report(&report);
+        assert!(xml.contains("tests=\"2\" failures=\"1\""), "{}", xml);
+        assert!(xml.contains("<failure message=\"out of date\">-old\n+new\n</failure>"), "{}", xml);
+    }
+}
@@ -0,0 +1,52 @@
+use serde_json::{json, Value};
+
+/// JSON Schema (draft-07) describing rc.config.json, kept in step with `Config`/`JobConfig`
+pub fn config_schema() -> Value {
+    let alias = json!({
+        "type": "object",
+        "description": "Path prefixes replaced in output paths, e.g. \"@\": \"../generated\". Targets are resolved against the config file path and must exist.",
+        "additionalProperties": { "type": "string" }
+    });
+
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "rc.config.json",
+        "type": "object",
+        "additionalProperties": false,
+        "properties": {
+            "alias": alias,
+            "libs": {
+                "type": "array",
+                "description": "Directories of shared JS helpers, relative to the config directory. Module parsers import them by bare name; script parsers get their functions as globals.",
+                "items": { "type": "string" }
+            },
+            "jobs": {
+                "type": "array",
+                "description": "Parser/content pairs run together by `js_evaluator jobs`.",
+                "items": {
+                    "type": "object",
+                    "additionalProperties": false,
+                    "required": ["parser", "content"],
+                    "properties": {
+                        "name": {
+                            "type": "string",
+                            "description": "Name used to select the job; defaults to the parser file name."
+                        },
+                        "parser": {
+                            "type": "string",
+                            "description": "JavaScript parser file, relative to the config directory."
+                        },
+                        "content": {
+                            "description": "Content file(s), relative to the config directory; the parser runs once per file.",
+                            "oneOf": [
+                                { "type": "string" },
+                                { "type": "array", "items": { "type": "string" }, "minItems": 1 }
+                            ]
+                        },
+                        "alias": alias
+                    }
+                }
+            }
+        }
+    })
+}
@@ -1,51 +1,343 @@
 use std::collections::HashMap;
-use clap::Parser;
-use serde::Deserialize;
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(author, version, about, long_about = None, args_conflicts_with_subcommands = true)]
 pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Path to rc.config.json (default: search upwards from the parser's directory, then the cwd)
+    #[arg(long, global = true, value_name = "CONFIG")]
+    pub config: Option<String>,
+
+    /// Without a subcommand, `<JS_FILE> <CONTENT_FILE>` behaves like `run`
+    #[command(flatten)]
+    pub run: RunArgs,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Scaffold an rc.config.json plus a sample parser and content file
+    Init(InitArgs),
+    /// Execute the parser functions and write their outputs (default)
+    Run(RunArgs),
+    /// Render in memory, diff against disk and exit non-zero if anything is out of date
+    Check(CheckArgs),
+    /// List the path and compose functions a parser exports
+    List(ParserArgs),
+    /// Remove the files a parser would generate from the given content
+    Clean(InputArgs),
+    /// Run the jobs listed in rc.config.json (all of them, or only the named ones)
+    Jobs(JobsArgs),
+    /// Print a JSON Schema for rc.config.json, for editor validation and completion
+    Schema,
+}
+
+// Parser and content file, given as flags or positionally
+#[derive(clap::Args, Debug)]
+pub struct InputArgs {
     /// Path to the JavaScript file
     #[arg(short, long, value_name = "FILE")]
     pub file: Option<String>,
 
-    /// Path to the content/parameter file
+    /// Path to the content/parameter file, or `-` to read it from stdin
     #[arg(short, long, value_name = "CONTENT")]
     pub content: Option<String>,
 
     /// Positional arguments (fallback if flags not used)
     #[arg(value_name = "ARGS")]
     pub positional: Vec<String>,
+
+    #[command(flatten)]
+    pub vars: VarsArgs,
+
+    #[command(flatten)]
+    pub filter: FilterArgs,
 }
-#[derive(Debug)]
+
+// Which functions to run, by name or `fn.tags` entry
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct FilterArgs {
+    /// Run only functions with this name or tag, repeatable
+    #[arg(long, value_name = "NAME|TAG")]
+    pub only: Vec<String>,
+
+    /// Do not run functions with this name or tag, repeatable
+    #[arg(long, value_name = "NAME|TAG")]
+    pub skip: Vec<String>,
+}
+
+// Variables handed to every parser function (second argument and `rc.vars`)
+#[derive(clap::Args, Debug)]
+pub struct VarsArgs {
+    /// Set a variable, repeatable: `--define basePackage=com.acme`
+    #[arg(short = 'D', long = "define", value_name = "KEY=VALUE")]
+    pub defines: Vec<String>,
+
+    /// JSON object of variables; `--define` entries override its keys
+    #[arg(long = "vars", value_name = "FILE")]
+    pub vars_file: Option<String>,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct RunArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    /// Print the planned file map (path, size, function) without writing anything
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Keep running and regenerate whenever the parser, its imports, `libs`, the content or rc.config.json changes
+    #[arg(long, conflicts_with = "dry_run")]
+    pub watch: bool,
+
+    /// Print rendered outputs to stdout instead of writing them (path headers when several)
+    #[arg(long, conflicts_with_all = ["dry_run", "watch", "report"])]
+    pub stdout: bool,
+
+    /// Write a machine-readable report of the run: `--report json|junit <FILE>`
+    #[arg(long, num_args = 2, value_names = ["FORMAT", "FILE"], conflicts_with = "dry_run")]
+    pub report: Option<Vec<String>>,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct CheckArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    /// Write a machine-readable report of the check: `--report json|junit <FILE>`
+    #[arg(long, num_args = 2, value_names = ["FORMAT", "FILE"])]
+    pub report: Option<Vec<String>>,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct ParserArgs {
+    /// Path to the JavaScript file
+    #[arg(value_name = "JS_FILE")]
+    pub file: String,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct InitArgs {
+    /// Directory to scaffold into
+    #[arg(value_name = "DIR", default_value = ".")]
+    pub dir: String,
+
+    /// Overwrite files that already exist
+    #[arg(long)]
+    pub force: bool,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct JobsArgs {
+    /// Names of the jobs to run (all jobs when omitted)
+    #[arg(value_name = "NAME")]
+    pub names: Vec<String>,
+
+    /// Print the planned file map of every job without writing anything
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Diff every job's outputs against disk instead of writing them
+    #[arg(long, conflicts_with = "dry_run")]
+    pub check: bool,
+
+    /// Write a machine-readable report of all jobs: `--report json|junit <FILE>`
+    #[arg(long, num_args = 2, value_names = ["FORMAT", "FILE"], conflicts_with = "dry_run")]
+    pub report: Option<Vec<String>>,
+
+    #[command(flatten)]
+    pub vars: VarsArgs,
+
+    #[command(flatten)]
+    pub filter: FilterArgs,
+}
+
+/// What a run does with the rendered files
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RunMode {
+    Write,
+    DryRun,
+    Check,
+    Stdout,
+}
+
+#[derive(Debug, Serialize)]
 pub struct SuccessResult {
     pub name: String,
     pub path: String,
+    pub bytes: usize,
+    pub status: FileStatus,
+}
+
+/// What happened to a file on disk
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileStatus {
+    Created,
+    Updated,
+    Unchanged,
+    Removed,
+}
+
+/// Everything a run produced, accumulated across jobs and serialized by `--report`
+#[derive(Debug, Default, Serialize)]
+pub struct RunReport {
+    pub config: Option<String>,
+    pub successes: Vec<SuccessResult>,
+    pub failures: Vec<FailureResult>,
+    pub skipped: Vec<SkippedFunction>,
+    pub warnings: Vec<ConsoleWarning>,
+}
+
+/// A `console.warn`/`console.error` call made by a parser, kept for the run report
+#[derive(Debug, Serialize)]
+pub struct ConsoleWarning {
+    pub name: String,
+    pub level: String,
+    pub message: String,
+}
+
+/// Machine-readable formats accepted by `--report`
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ReportFormat {
+    Json,
+    Junit,
+}
+
+/// A file rendered in memory by a path or compose function, not yet written to disk
+#[derive(Debug)]
+pub struct RenderedFile {
+    pub name: String,
+    pub path: String,
+    pub content: FileContent,
+    pub random_path: bool, // a compose result without paths, written under a fresh `make_uuid_for_path_not` name
+}
+
+/// A file's content: text, or raw bytes from a `Uint8Array`, `ArrayBuffer` or `{base64}` result
+#[derive(Debug, Clone, PartialEq)]
+pub enum FileContent {
+    Text(String),
+    Binary(Vec<u8>),
+}
+
+impl FileContent {
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            FileContent::Text(text) => text.as_bytes(),
+            FileContent::Binary(bytes) => bytes,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.as_bytes().len()
+    }
+}
+
+/// Outcome of comparing one rendered file with its on-disk counterpart
+#[derive(Debug)]
+pub struct CheckResult {
+    pub name: String,
+    pub path: String,
+    pub bytes: usize,
+    pub diff: Option<String>, // unified diff, None when up to date
 }
 
 /// Enhanced function result with type information
 #[derive(Debug)]
 pub enum FunctionReturnType {
-    Array2D(Vec<(String, String)>),  // 2D array: [["k1","v1"], ["k2","v2"]]
-    Object(Vec<(String, String)>),   // Object: {k1: "v1", k2: "v2"}
+    Array2D(Vec<(String, FileContent)>),  // 2D array: [["k1","v1"], ["k2","v2"]]
+    Object(Vec<(String, FileContent)>),   // Object: {k1: "v1", k2: "v2"}
     String(String),                   // Everything else converted to string (JS way)
+    Binary(Vec<u8>),                  // Uint8Array, ArrayBuffer or {base64: "..."}
 }
 
 // 定义与JSON结构匹配的结构体
 #[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct Config {
-    pub alias: Option<HashMap<String, String>>, 
+    pub alias: Option<HashMap<String, String>>,
+    pub jobs: Option<Vec<JobConfig>>,
+    pub libs: Option<Vec<String>>, // shared helper directories, relative to the config directory
+    #[serde(skip)]
+    pub path: Option<String>, // the rc.config.json this was loaded from, if any
+    #[serde(skip)]
+    pub vars: Map<String, Value>, // `--vars`/`--define` from the command line
+    #[serde(skip)]
+    pub filter: FilterArgs, // `--only`/`--skip` from the command line
 }
 
-#[derive(Debug)]
+/// One parser run described in rc.config.json: a parser, its content file(s) and extra aliases
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JobConfig {
+    pub name: Option<String>,
+    pub parser: String,
+    pub content: ContentFiles,
+    pub alias: Option<HashMap<String, String>>,
+}
+
+/// A job's content: either a single file or a list of files, each run separately
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum ContentFiles {
+    One(String),
+    Many(Vec<String>),
+}
+
+#[derive(Debug, Serialize)]
 pub struct FailureResult {
     pub name: String,
     pub error: String,  // 仅存错误信息
     pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diff: Option<String>, // `check`: the unified diff of an out-of-date file
+}
+
+/// A function that did not run in this render, and why
+#[derive(Debug, Serialize)]
+pub struct SkippedFunction {
+    pub name: String,
+    pub reason: SkipReason,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    Disabled,  // `fn.enabled = false`
+    NotOnly,   // matches no `--only` entry
+    Skip,      // matches a `--skip` entry
+    WhenFalse, // `fn.when` returned a falsy value
+    RandomPath, // `check`/`clean`: a compose result without paths gets a new random file name every run
+}
+
+impl SkipReason {
+    pub fn describe(self) -> &'static str {
+        match self {
+            SkipReason::Disabled => "fn.enabled is false",
+            SkipReason::NotOnly => "not selected by --only",
+            SkipReason::Skip => "excluded by --skip",
+            SkipReason::WhenFalse => "when() returned false",
+            SkipReason::RandomPath => {
+                "written under a random file name each run, so it cannot be checked or cleaned"
+            }
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct FunctionForExecute {
     pub function_name: String,
     pub path: String,
+    pub computed_path: bool,   // `fn.path` is a function `(content, meta) => string`, called per run
+    pub input: Option<String>, // `fn.input`: decode the content as json/jsonc/yaml/toml/csv/auto
+    pub after: Vec<String>,    // `fn.after`: functions that must run first; their outputs are passed in
+    pub description: Option<String>, // `fn.description`, shown by `list`
+    pub enabled: bool,         // `fn.enabled = false` turns the function off
+    pub tags: Vec<String>,     // `fn.tags`, matched by `--only`/`--skip` like names
+    pub conditional: bool,     // `fn.when` is set, so whether it runs depends on the content
 }
\ No newline at end of file
@@ -0,0 +1,16 @@
+//! Process exit codes, so scripts can tell how a generation run ended.
+
+/// Every function ran and every file was written (or is up to date in `check`)
+pub const SUCCESS: i32 = 0;
+/// At least one function or file write failed, or `check` found outdated files
+pub const FUNCTIONS_FAILED: i32 = 1;
+/// Invalid arguments (including an `--only` that matches no function name or tag), or an
+/// input/output file could not be read or written (clap also uses 2)
+pub const USAGE_ERROR: i32 = 2;
+/// The parser could not be evaluated by the JavaScript engine
+pub const JS_EVALUATION_FAILED: i32 = 3;
+/// rc.config.json exists but could not be read or parsed
+pub const CONFIG_INVALID: i32 = 4;
+/// The parser evaluated fine but exports no path or compose functions (functions left out by
+/// `--only`, `--skip`, `fn.enabled` or `fn.when` are a success, not this)
+pub const NO_FUNCTIONS: i32 = 5;
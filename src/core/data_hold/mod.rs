@@ -1 +1,3 @@
-pub mod data_struct;
\ No newline at end of file
+pub mod config_schema;
+pub mod data_struct;
+pub mod exit_code;
@@ -1,137 +1,732 @@
 use crate::core::boa_js_specific::call_func::call_function;
+use crate::core::boa_js_specific::evaluate_parser::{self, evaluate_parser, parser_dir};
+use crate::core::boa_js_specific::host_console::ParserConsole;
+use crate::core::boa_js_specific::host_rc::HostPaths;
+use crate::core::console_print::print_console::print_skipped;
+use crate::core::boa_js_specific::settle_promise::settle_promise;
 use crate::core::data_hold::data_struct::{
-    Config, FailureResult, FunctionReturnType, SuccessResult,
+    CheckResult, Config, ConsoleWarning, FailureResult, FileContent, FileStatus, FunctionForExecute, FunctionReturnType, RenderedFile,
+    SkipReason, SkippedFunction, SuccessResult,
 };
-use crate::core::fs_handles::handle_file::handle_file;
+use crate::core::fs_handles::check_against_disk::diff_against_disk;
+use crate::core::fs_handles::handle_file::remove_file_if_exists;
 use crate::core::fs_handles::normalize_path::normalize_path;
 use crate::core::fs_handles::replace_prefix_or_not::replace_prefix_longest;
 use crate::core::functions_handles::call_in_compose::call_function_typed;
+use crate::core::functions_handles::decode_content::{decode_content, input_format};
 use crate::core::functions_handles::find_exported_functions::{
-    find_composed_functions, find_exported_functions,
+    find_composed_functions, find_exported_functions, skip_reason,
 };
 use crate::core::functions_handles::make_uuid_for_path_not::make_uuid_for_path_not;
-use crate::core::functions_handles::write_fs_kv_pairs::write_pairs;
-use boa_engine::{Context, Source};
-use std::path::PathBuf;
+use crate::core::functions_handles::write_fs_kv_pairs::write_rendered_files;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use boa_engine::object::ObjectInitializer;
+use boa_engine::property::Attribute;
+use boa_engine::{js_string, Context, JsObject, JsString, JsValue};
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
-pub fn execute_exported_functions(
+/// Evaluate the parser and render every path/compose function in memory, without touching disk.
+/// Console warnings and errors logged by the parser are appended to `warnings`, and functions
+/// left out by `fn.enabled`, `--only`, `--skip` or `fn.when` to `skipped`.
+pub fn render_exported_functions(
     js_code: &str,
     content: &str,
+    content_file: &str,
     js_file_str: &str,
     generation_config: &Config,
-) -> Result<(Vec<SuccessResult>, Vec<FailureResult>), String> {
-    // Create JavaScript context
-    let mut context = Context::default();
+    warnings: &mut Vec<ConsoleWarning>,
+    skipped: &mut Vec<SkippedFunction>,
+) -> Result<(Vec<RenderedFile>, Vec<FailureResult>), String> {
+    let console = ParserConsole::new(&parser_file_name(js_file_str));
+    let result =
+        render_with_console(js_code, content, content_file, js_file_str, generation_config, &console, skipped);
+    warnings.extend(console.take_warnings());
+    result
+}
 
-    // Evaluate the JavaScript code
-    context
-        .eval(Source::from_bytes(js_code))
-        .map_err(|e| format!("Failed to evaluate JavaScript: {}", e))?;
+fn render_with_console(
+    js_code: &str,
+    content: &str,
+    content_file: &str,
+    js_file_str: &str,
+    generation_config: &Config,
+    console: &ParserConsole,
+    skipped: &mut Vec<SkippedFunction>,
+) -> Result<(Vec<RenderedFile>, Vec<FailureResult>), String> {
+    // Evaluate the parser as a script or an ES module
+    let (mut context, functions) = evaluate_parser(js_code, js_file_str, generation_config, console)?;
+
+    // `--vars`/`--define`, passed to every function after the content
+    let vars = JsValue::from_json(&Value::Object(generation_config.vars.clone()), &mut context)
+        .map_err(|e| format!("Failed to convert vars: {}", e))?;
 
     // Find all functions with "path" property
-    let exported_functions = find_exported_functions(&mut context)?;
+    let exported_functions = find_exported_functions(&mut context, &functions)?;
 
     // Find all compose functions with "compose" property
-    let composed_functions = find_composed_functions(&mut context)?;
+    let composed_functions = find_composed_functions(&mut context, &functions)?;
 
-    let mut successes = Vec::new();
+    let mut rendered = Vec::new();
     let mut failures = Vec::new();
 
     if exported_functions.is_empty() && composed_functions.is_empty() {
-        return Ok((successes, failures));
+        return Ok((rendered, failures));
     }
 
     // Store the temporary parent dir
     let current_path = PathBuf::from(js_file_str);
-    let current_path_dir = current_path.parent().unwrap();
+    let env = RenderEnv {
+        functions: &functions,
+        vars,
+        // Templates returned as `{template, data}` resolve like `rc.render` paths
+        host_paths: HostPaths::for_parser(generation_config, &parser_dir(js_file_str)),
+        path_alias: generation_config.alias.clone().unwrap_or_default(),
+        current_path_dir: current_path.parent().unwrap(),
+        content,
+        content_file,
+        parser_file: js_file_str,
+        console,
+    };
 
-    let path_alias = generation_config.alias.clone().unwrap();
+    // Path functions, then compose functions, reordered so each runs after its `fn.after` list
+    let scheduled: Vec<ScheduledFunction> = exported_functions
+        .into_iter()
+        .map(|function| ScheduledFunction { function, compose: false })
+        .chain(
+            composed_functions
+                .into_iter()
+                .map(|function| ScheduledFunction { function, compose: true }),
+        )
+        .collect();
+    let known: HashSet<String> = scheduled
+        .iter()
+        .map(|job| job.function.function_name.clone())
+        .collect();
 
-    println!("config here {:?}", path_alias);
+    // `fn.enabled = false`, `--only` and `--skip`
+    let mut excluded = HashSet::new();
+    let scheduled: Vec<ScheduledFunction> = scheduled
+        .into_iter()
+        .filter(|job| match skip_reason(&job.function, &generation_config.filter) {
+            Some(reason) => {
+                let name = job.function.function_name.clone();
+                print_skipped(&name, reason);
+                excluded.insert(name.clone());
+                skipped.push(SkippedFunction { name, reason });
+                false
+            }
+            None => true,
+        })
+        .collect();
+    let (ordered, cycles) = schedule_functions(scheduled);
 
-    // Call each exported function with the content
-    for func_item in exported_functions {
-        let function_name = func_item.function_name;
-        let target_path = {
-            let path = match replace_prefix_longest(func_item.path.as_str(), &path_alias) {
-                Ok(path) => PathBuf::from(path),
-                Err(_) => current_path_dir.join(func_item.path),
-            };
-            normalize_path(&*path).to_string_lossy().to_string()
+    let mut failed = HashSet::new();
+    for (job, error) in cycles {
+        failed.insert(job.function.function_name.clone());
+        failures.push(env.failure(&job, error));
+    }
+    let mut outputs: HashMap<String, Value> = HashMap::new();
+    let mut decoded = HashMap::new();
+
+    for job in ordered {
+        let name = job.function.function_name.clone();
+        let result = match blocked_by(&job.function.after, &known, &excluded, &failed) {
+            Some(error) => Err(error),
+            None => env.run(&mut context, &job, &mut decoded, &outputs),
         };
-        let result = call_function(&mut context, &function_name, content);
-        match handle_file(&*target_path, result?.as_str()) {
-            Ok(_result) => {
-                successes.push(SuccessResult {
-                    name: function_name,
-                    path: target_path.to_string(),
+
+        match result {
+            Ok(None) => {
+                print_skipped(&name, SkipReason::WhenFalse);
+                outputs.insert(name.clone(), Value::Array(Vec::new()));
+                skipped.push(SkippedFunction {
+                    name,
+                    reason: SkipReason::WhenFalse,
                 });
             }
-            Err(e) => {
-                failures.push(FailureResult {
-                    name: function_name,
-                    error: e.to_string(),
-                    path: target_path.to_string(),
-                });
+            Ok(Some(files)) => {
+                let files_json = files
+                    .iter()
+                    .map(|file| match &file.content {
+                        FileContent::Text(text) => json!({ "path": file.path, "content": text }),
+                        FileContent::Binary(bytes) => json!({ "path": file.path, "base64": BASE64.encode(bytes) }),
+                    })
+                    .collect();
+                outputs.insert(name, Value::Array(files_json));
+                rendered.extend(files);
             }
-        };
+            Err(error) => {
+                failed.insert(name);
+                failures.push(env.failure(&job, error));
+            }
+        }
     }
 
-    // Handle the compose function calls
-    for composed_function in composed_functions {
-        let result = call_function_typed(
-            &mut context,
-            &*composed_function.function_name,
-            content,
-            &path_alias,
-            current_path_dir,
-        );
-        match result {
-            Ok(result) => match result {
-                FunctionReturnType::Array2D(path_content_pairs) => {
-                    write_pairs(
-                        &path_content_pairs,
-                        &mut successes,
-                        &mut failures,
-                        composed_function.function_name,
-                    );
-                }
-                FunctionReturnType::Object(path_content_pairs) => {
-                    write_pairs(
-                        &path_content_pairs,
-                        &mut successes,
-                        &mut failures,
-                        composed_function.function_name,
-                    );
-                }
-                FunctionReturnType::String(string_content) => {
-                    let newly_path = make_uuid_for_path_not(&composed_function.function_name);
-                    match handle_file(&*newly_path, &*string_content) {
-                        Ok(_) => {
-                            successes.push(SuccessResult {
-                                name: composed_function.function_name,
-                                path: newly_path.to_string(),
-                            });
-                        }
-                        Err(e) => {
-                            failures.push(FailureResult {
-                                error: e.to_string(),
-                                name: composed_function.function_name,
-                                path: newly_path.to_string(),
-                            });
-                        }
-                    }
+    Ok((rendered, failures))
+}
+
+/// A path or compose function waiting to be called
+struct ScheduledFunction {
+    function: FunctionForExecute,
+    compose: bool,
+}
+
+/// A function that cannot run, with the reason
+type Unschedulable = (ScheduledFunction, String);
+
+/// Order the functions so each runs after every function named in its `fn.after`,
+/// otherwise keeping the given order. Functions caught in (or waiting on) a dependency cycle
+/// cannot run and come back separately with the cycle. Unknown names are left to the caller.
+fn schedule_functions(jobs: Vec<ScheduledFunction>) -> (Vec<ScheduledFunction>, Vec<Unschedulable>) {
+    let index_of: HashMap<&str, usize> = jobs
+        .iter()
+        .enumerate()
+        .map(|(i, job)| (job.function.function_name.as_str(), i))
+        .collect();
+    let dependencies: Vec<Vec<usize>> = jobs
+        .iter()
+        .map(|job| {
+            job.function
+                .after
+                .iter()
+                .filter_map(|name| index_of.get(name.as_str()).copied())
+                .collect()
+        })
+        .collect();
+
+    // Kahn's algorithm, always taking the earliest ready function to keep the order stable
+    let mut waiting_on: Vec<usize> = dependencies.iter().map(Vec::len).collect();
+    let mut ready: BTreeSet<usize> = (0..jobs.len()).filter(|&i| waiting_on[i] == 0).collect();
+    let mut order = Vec::with_capacity(jobs.len());
+    while let Some(i) = ready.pop_first() {
+        order.push(i);
+        for (dependent, deps) in dependencies.iter().enumerate() {
+            for _ in deps.iter().filter(|&&dep| dep == i) {
+                waiting_on[dependent] -= 1;
+                if waiting_on[dependent] == 0 {
+                    ready.insert(dependent);
                 }
-            },
-            Err(e) => {
-                failures.push(FailureResult {
-                    name: composed_function.function_name,
-                    error: e.to_string(),
-                    path: "".to_string(),
-                });
             }
+        }
+    }
+
+    let cycle_errors: Vec<Option<String>> = (0..jobs.len())
+        .map(|i| (waiting_on[i] > 0).then(|| describe_cycle(&jobs, &dependencies, &waiting_on, i)))
+        .collect();
+
+    let mut slots: Vec<Option<ScheduledFunction>> = jobs.into_iter().map(Some).collect();
+    let ordered = order.into_iter().filter_map(|i| slots[i].take()).collect();
+    let unschedulable = slots
+        .into_iter()
+        .zip(cycle_errors)
+        .filter_map(|(job, error)| Some((job?, error?)))
+        .collect();
+    (ordered, unschedulable)
+}
+
+/// Why a function cannot run yet although it was scheduled: a name in its `fn.after` is
+/// unknown, was left out of this run, or failed (which also fails everything waiting on it)
+fn blocked_by(
+    after: &[String],
+    known: &HashSet<String>,
+    excluded: &HashSet<String>,
+    failed: &HashSet<String>,
+) -> Option<String> {
+    after.iter().find_map(|dependency| {
+        if !known.contains(dependency) {
+            Some(format!("after: there is no path or compose function named '{}'", dependency))
+        } else if excluded.contains(dependency) {
+            Some(format!("after: '{}' is disabled or not selected", dependency))
+        } else if failed.contains(dependency) {
+            Some(format!("after: skipped because '{}' failed", dependency))
+        } else {
+            None
+        }
+    })
+}
+
+/// Follow unfinished dependencies from `start` until a function repeats, and name the cycle
+fn describe_cycle(
+    jobs: &[ScheduledFunction],
+    dependencies: &[Vec<usize>],
+    waiting_on: &[usize],
+    start: usize,
+) -> String {
+    let mut path = vec![start];
+    let mut current = start;
+    while let Some(&next) = dependencies[current].iter().find(|&&dep| waiting_on[dep] > 0) {
+        if let Some(position) = path.iter().position(|&i| i == next) {
+            path.drain(..position);
+            path.push(next);
+            break;
+        }
+        path.push(next);
+        current = next;
+    }
+    let in_cycle = path.first() == Some(&start);
+    let cycle = path
+        .iter()
+        .map(|&i| jobs[i].function.function_name.as_str())
+        .collect::<Vec<_>>()
+        .join(" -> ");
+    if in_cycle {
+        format!("after: dependency cycle {}", cycle)
+    } else {
+        format!("after: waits on the dependency cycle {}", cycle)
+    }
+}
+
+/// What every function call shares within one render
+struct RenderEnv<'a> {
+    functions: &'a JsObject,
+    vars: JsValue,
+    host_paths: HostPaths,
+    path_alias: HashMap<String, String>,
+    current_path_dir: &'a Path,
+    content: &'a str,
+    content_file: &'a str,
+    parser_file: &'a str,
+    console: &'a ParserConsole,
+}
+
+impl RenderEnv<'_> {
+    /// Where a path function writes: its path with the alias applied, or relative to the parser
+    fn target_path(&self, path: &str) -> String {
+        let path = match replace_prefix_longest(path, &self.path_alias) {
+            Ok(path) => PathBuf::from(path),
+            Err(_) => self.current_path_dir.join(path),
+        };
+        normalize_path(&path).to_string_lossy().to_string()
+    }
+
+    /// A failure of `job`; compose functions and computed paths have no single path to report
+    fn failure(&self, job: &ScheduledFunction, error: String) -> FailureResult {
+        let path = if job.compose || job.function.computed_path {
+            "".to_string()
+        } else {
+            self.target_path(&job.function.path)
+        };
+        FailureResult {
+            name: job.function.function_name.clone(),
+            error,
+            path,
+            diff: None,
+        }
+    }
+
+    /// `fn.path(content, meta)` for a computed path, where `meta` is
+    /// `{name, parser, contentFile, vars}`
+    fn computed_path(&self, context: &mut Context, name: &str, content: &JsValue) -> Result<String, String> {
+        let path_function = self
+            .functions
+            .get(JsString::from(name), context)
+            .and_then(|function| match function.as_object() {
+                Some(object) => object.get(js_string!("path"), context),
+                None => Ok(JsValue::undefined()),
+            })
+            .map_err(|e| format!("Failed to get path: {}", e))?;
+        let Some(path_function) = path_function.as_callable() else {
+            return Err("fn.path is no longer a function".to_string());
+        };
+
+        let meta = ObjectInitializer::new(context)
+            .property(js_string!("name"), JsString::from(name), Attribute::all())
+            .property(js_string!("parser"), JsString::from(self.parser_file), Attribute::all())
+            .property(js_string!("contentFile"), JsString::from(self.content_file), Attribute::all())
+            .property(js_string!("vars"), self.vars.clone(), Attribute::all())
+            .build();
+        let path = path_function
+            .call(&JsValue::undefined(), &[content.clone(), meta.into()], context)
+            .map_err(|e| format!("fn.path: {}", e))?;
+        let path = settle_promise(context, path).map_err(|e| format!("fn.path: {}", e))?;
+        match path.as_string() {
+            Some(path) if !path.is_empty() => Ok(path.to_std_string_escaped()),
+            _ => Err(format!("fn.path must return a non-empty string, got {}", path.display())),
+        }
+    }
+
+    /// `fn.when(content, vars, outputs)`: the function only runs when this returns a truthy value
+    fn when_allows(&self, context: &mut Context, name: &str, args: &[JsValue]) -> Result<bool, String> {
+        let function = self
+            .functions
+            .get(JsString::from(name), context)
+            .map_err(|e| format!("Failed to get function: {}", e))?;
+        let when = match function.as_object() {
+            Some(object) => object
+                .get(js_string!("when"), context)
+                .map_err(|e| format!("Failed to get when: {}", e))?,
+            None => return Ok(true),
         };
+        let Some(predicate) = when.as_callable() else {
+            return Ok(true);
+        };
+        let result = predicate
+            .call(&JsValue::undefined(), args, context)
+            .map_err(|e| format!("when: {}", e))?;
+        let result = settle_promise(context, result).map_err(|e| format!("when: {}", e))?;
+        Ok(result.to_boolean())
     }
 
+    /// Call one function with the content, vars and its dependencies' outputs.
+    /// `None` when `fn.when` says it should not run.
+    fn run(
+        &self,
+        context: &mut Context,
+        job: &ScheduledFunction,
+        decoded: &mut HashMap<String, Result<Value, String>>,
+        outputs: &HashMap<String, Value>,
+    ) -> Result<Option<Vec<RenderedFile>>, String> {
+        let function = &job.function;
+        let name = &function.function_name;
+        let content_value = content_argument(context, &function.input, self.content, self.content_file, decoded)?;
+        let outputs_value = outputs_argument(context, &function.after, outputs)?;
+        let args = [content_value, self.vars.clone(), outputs_value];
+        self.console.set_function(name);
+        if !self.when_allows(context, name, &args)? {
+            return Ok(None);
+        }
+
+        if !job.compose {
+            let path = if function.computed_path {
+                self.computed_path(context, name, &args[0])?
+            } else {
+                function.path.clone()
+            };
+            let (path_override, output) = call_function(context, self.functions, name, &args, &self.host_paths)?;
+            return Ok(Some(vec![RenderedFile {
+                name: name.clone(),
+                path: self.target_path(&path_override.unwrap_or(path)),
+                content: output,
+                random_path: false,
+            }]));
+        }
+
+        let result = call_function_typed(
+            context,
+            self.functions,
+            name,
+            &args,
+            &self.path_alias,
+            self.current_path_dir,
+        )?;
+        let files = match result {
+            FunctionReturnType::Array2D(path_content_pairs)
+            | FunctionReturnType::Object(path_content_pairs) => path_content_pairs
+                .into_iter()
+                .map(|(path, file_content)| RenderedFile {
+                    name: name.clone(),
+                    path,
+                    content: file_content,
+                    random_path: false,
+                })
+                .collect(),
+            FunctionReturnType::String(string_content) => vec![RenderedFile {
+                name: name.clone(),
+                path: make_uuid_for_path_not(name),
+                content: FileContent::Text(string_content),
+                random_path: true,
+            }],
+            FunctionReturnType::Binary(bytes) => vec![RenderedFile {
+                name: name.clone(),
+                path: make_uuid_for_path_not(name),
+                content: FileContent::Binary(bytes),
+                random_path: true,
+            }],
+        };
+        Ok(Some(files))
+    }
+}
+
+/// The third argument passed to a function: `{dependency: [{path, content}, ...]}` holding the
+/// files rendered by each function in its `fn.after` (binary files carry `base64` instead of `content`)
+fn outputs_argument(
+    context: &mut Context,
+    after: &[String],
+    outputs: &HashMap<String, Value>,
+) -> Result<JsValue, String> {
+    let dependencies: Map<String, Value> = after
+        .iter()
+        .filter_map(|name| outputs.get(name).map(|files| (name.clone(), files.clone())))
+        .collect();
+    JsValue::from_json(&Value::Object(dependencies), context)
+        .map_err(|e| format!("Failed to convert earlier outputs: {}", e))
+}
+
+/// The first argument passed to a function: the raw content, or the content decoded as
+/// `fn.input` asks. Each format is decoded once per run and shared between functions.
+fn content_argument(
+    context: &mut Context,
+    input: &Option<String>,
+    content: &str,
+    content_file: &str,
+    decoded: &mut HashMap<String, Result<Value, String>>,
+) -> Result<JsValue, String> {
+    let Some(requested) = input else {
+        return Ok(JsValue::from(JsString::from(content)));
+    };
+    let format = input_format(requested, content_file)?;
+    let value = decoded
+        .entry(format.clone())
+        .or_insert_with(|| decode_content(&format, content, content_file))
+        .clone()?;
+    JsValue::from_json(&value, context).map_err(|e| format!("Failed to convert content: {}", e))
+}
+
+pub fn execute_exported_functions(
+    js_code: &str,
+    content: &str,
+    content_file: &str,
+    js_file_str: &str,
+    generation_config: &Config,
+    warnings: &mut Vec<ConsoleWarning>,
+    skipped: &mut Vec<SkippedFunction>,
+) -> Result<(Vec<SuccessResult>, Vec<FailureResult>), String> {
+    let (rendered, mut failures) = render_exported_functions(
+        js_code,
+        content,
+        content_file,
+        js_file_str,
+        generation_config,
+        warnings,
+        skipped,
+    )?;
+
+    let mut successes = Vec::new();
+    write_rendered_files(rendered, &mut successes, &mut failures);
+
     Ok((successes, failures))
 }
+
+/// Render every function in memory and compare each result with the file on disk
+pub fn check_exported_functions(
+    js_code: &str,
+    content: &str,
+    content_file: &str,
+    js_file_str: &str,
+    generation_config: &Config,
+    skipped: &mut Vec<SkippedFunction>,
+) -> Result<(Vec<CheckResult>, Vec<FailureResult>), String> {
+    let (rendered, mut failures) = render_exported_functions(
+        js_code,
+        content,
+        content_file,
+        js_file_str,
+        generation_config,
+        &mut Vec::new(),
+        skipped,
+    )?;
+
+    let mut checked = Vec::new();
+    for file in rendered {
+        // A fresh random path never matches what an earlier run wrote
+        if file.random_path {
+            print_skipped(&file.name, SkipReason::RandomPath);
+            skipped.push(SkippedFunction {
+                name: file.name,
+                reason: SkipReason::RandomPath,
+            });
+            continue;
+        }
+        match diff_against_disk(&file) {
+            Ok(diff) => checked.push(CheckResult {
+                name: file.name,
+                path: file.path,
+                bytes: file.content.len(),
+                diff,
+            }),
+            Err(e) => failures.push(FailureResult {
+                name: file.name,
+                error: e.to_string(),
+                path: file.path,
+                diff: None,
+            }),
+        }
+    }
+
+    Ok((checked, failures))
+}
+
+/// Evaluate the parser and return its path functions and compose functions, without calling them
+pub fn list_exported_functions(
+    js_code: &str,
+    js_file_str: &str,
+    generation_config: &Config,
+) -> Result<(Vec<FunctionForExecute>, Vec<FunctionForExecute>), String> {
+    let console = ParserConsole::new(&parser_file_name(js_file_str));
+    let (mut context, functions) =
+        evaluate_parser(js_code, js_file_str, generation_config, &console)?;
+
+    let exported_functions = find_exported_functions(&mut context, &functions)?;
+    let composed_functions = find_composed_functions(&mut context, &functions)?;
+
+    Ok((exported_functions, composed_functions))
+}
+
+/// The imported modules and `libs` files a parser depends on, for `--watch`; no parser code runs
+pub fn parser_sources(js_code: &str, js_file_str: &str, generation_config: &Config) -> Vec<PathBuf> {
+    evaluate_parser::parser_sources(js_code, js_file_str, generation_config)
+}
+
+/// Render every function in memory and remove the files it would produce.
+/// Only files that actually existed are reported as removed.
+pub fn clean_exported_functions(
+    js_code: &str,
+    content: &str,
+    content_file: &str,
+    js_file_str: &str,
+    generation_config: &Config,
+    skipped: &mut Vec<SkippedFunction>,
+) -> Result<(Vec<SuccessResult>, Vec<FailureResult>), String> {
+    let (rendered, mut failures) = render_exported_functions(
+        js_code,
+        content,
+        content_file,
+        js_file_str,
+        generation_config,
+        &mut Vec::new(),
+        skipped,
+    )?;
+
+    let mut removed = Vec::new();
+    for file in rendered {
+        // The file an earlier run wrote had a different random name
+        if file.random_path {
+            print_skipped(&file.name, SkipReason::RandomPath);
+            skipped.push(SkippedFunction {
+                name: file.name,
+                reason: SkipReason::RandomPath,
+            });
+            continue;
+        }
+        match remove_file_if_exists(&file.path) {
+            Ok(true) => removed.push(SuccessResult {
+                name: file.name,
+                path: file.path,
+                bytes: 0,
+                status: FileStatus::Removed,
+            }),
+            Ok(false) => {}
+            Err(e) => failures.push(FailureResult {
+                name: file.name,
+                error: e.to_string(),
+                path: file.path,
+                diff: None,
+            }),
+        }
+    }
+
+    Ok((removed, failures))
+}
+
+/// Console prefix for code that runs outside any function, e.g. `parser.js`
+fn parser_file_name(js_file_str: &str) -> String {
+    Path::new(js_file_str)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| js_file_str.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(name: &str, after: &[&str]) -> ScheduledFunction {
+        ScheduledFunction {
+            function: FunctionForExecute {
+                function_name: name.to_string(),
+                path: format!("{}.txt", name),
+                computed_path: false,
+                input: None,
+                after: after.iter().map(|dependency| dependency.to_string()).collect(),
+                description: None,
+                enabled: true,
+                tags: Vec::new(),
+                conditional: false,
+            },
+            compose: false,
+        }
+    }
+
+    fn names(jobs: &[ScheduledFunction]) -> Vec<&str> {
+        jobs.iter().map(|job| job.function.function_name.as_str()).collect()
+    }
+
+    fn errors(unschedulable: &[Unschedulable]) -> Vec<(&str, &str)> {
+        unschedulable
+            .iter()
+            .map(|(job, error)| (job.function.function_name.as_str(), error.as_str()))
+            .collect()
+    }
+
+    fn set(names: &[&str]) -> HashSet<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn keeps_definition_order_without_after() {
+        let (ordered, unschedulable) = schedule_functions(vec![job("c", &[]), job("a", &[]), job("b", &[])]);
+        assert_eq!(names(&ordered), ["c", "a", "b"]);
+        assert!(unschedulable.is_empty());
+    }
+
+    #[test]
+    fn runs_functions_after_their_dependencies() {
+        let jobs = vec![job("index", &["model", "api"]), job("model", &[]), job("api", &["model"]), job("other", &[])];
+        let (ordered, unschedulable) = schedule_functions(jobs);
+        assert_eq!(names(&ordered), ["model", "api", "index", "other"]);
+        assert!(unschedulable.is_empty());
+    }
+
+    #[test]
+    fn reports_two_function_cycles_and_their_dependents() {
+        let jobs = vec![job("a", &["b"]), job("b", &["a"]), job("c", &["a"]), job("d", &[])];
+        let (ordered, unschedulable) = schedule_functions(jobs);
+        assert_eq!(names(&ordered), ["d"]);
+        assert_eq!(
+            errors(&unschedulable),
+            [
+                ("a", "after: dependency cycle a -> b -> a"),
+                ("b", "after: dependency cycle b -> a -> b"),
+                ("c", "after: waits on the dependency cycle a -> b -> a"),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_self_cycles() {
+        let (ordered, unschedulable) = schedule_functions(vec![job("a", &["a"]), job("b", &[])]);
+        assert_eq!(names(&ordered), ["b"]);
+        assert_eq!(errors(&unschedulable), [("a", "after: dependency cycle a -> a")]);
+    }
+
+    #[test]
+    fn unknown_names_are_left_to_the_caller() {
+        let (ordered, unschedulable) = schedule_functions(vec![job("a", &["missing"])]);
+        assert_eq!(names(&ordered), ["a"]);
+        assert!(unschedulable.is_empty());
+
+        let after = ["missing".to_string()];
+        assert_eq!(
+            blocked_by(&after, &set(&["a"]), &set(&[]), &set(&[])).as_deref(),
+            Some("after: there is no path or compose function named 'missing'")
+        );
+    }
+
+    #[test]
+    fn excluded_and_failed_dependencies_block_their_dependents() {
+        let known = set(&["model", "api", "index"]);
+        let after = ["model".to_string(), "api".to_string()];
+        assert_eq!(blocked_by(&after, &known, &set(&[]), &set(&[])), None);
+        assert_eq!(
+            blocked_by(&after, &known, &set(&["model"]), &set(&[])).as_deref(),
+            Some("after: 'model' is disabled or not selected")
+        );
+        assert_eq!(
+            blocked_by(&after, &known, &set(&[]), &set(&["api"])).as_deref(),
+            Some("after: skipped because 'api' failed")
+        );
+    }
+}
@@ -0,0 +1,77 @@
+use crate::core::data_hold::data_struct::{FileContent, RenderedFile};
+use similar::TextDiff;
+use std::{fs, io};
+
+/// Compare a rendered file byte for byte with what is on disk.
+/// Returns a unified diff when they differ, `None` when the file is up to date.
+pub fn diff_against_disk(file: &RenderedFile) -> io::Result<Option<String>> {
+    let (on_disk, old_header) = match fs::read(&file.path) {
+        Ok(bytes) => (bytes, file.path.clone()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => (Vec::new(), "/dev/null".to_string()),
+        Err(e) => return Err(e),
+    };
+
+    if on_disk == file.content.as_bytes() {
+        return Ok(None);
+    }
+
+    let new_text = match &file.content {
+        FileContent::Text(text) => text,
+        FileContent::Binary(bytes) => {
+            return Ok(Some(format!(
+                "--- {}\n+++ {}\nBinary files differ ({} bytes on disk, {} bytes rendered)\n",
+                old_header,
+                file.path,
+                on_disk.len(),
+                bytes.len()
+            )))
+        }
+    };
+    let old_text = String::from_utf8_lossy(&on_disk);
+    // A full-file diff of lines that only differ in their `\r` shows no visible change
+    if let Some(note) = line_ending_note(&old_text, new_text) {
+        return Ok(Some(format!("--- {}\n+++ {}\n{}\n", old_header, file.path, note)));
+    }
+    let diff = TextDiff::from_lines(old_text.as_ref(), new_text.as_str())
+        .unified_diff()
+        .header(&old_header, &file.path)
+        .to_string();
+
+    Ok(Some(diff))
+}
+
+/// Describe the difference when two texts are equal once `\r\n` is normalized to `\n`
+fn line_ending_note(on_disk: &str, rendered: &str) -> Option<String> {
+    if on_disk == rendered || on_disk.replace("\r\n", "\n") != rendered.replace("\r\n", "\n") {
+        return None;
+    }
+    let ending = |text: &str| if text.contains("\r\n") { "CRLF" } else { "LF" };
+    Some(format!(
+        "line endings differ ({} rendered, {} on disk)",
+        ending(rendered),
+        ending(on_disk)
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_line_endings_differ() {
+        assert_eq!(
+            line_ending_note("a\nb\n", "a\r\nb\r\n").as_deref(),
+            Some("line endings differ (CRLF rendered, LF on disk)")
+        );
+        assert_eq!(
+            line_ending_note("a\r\nb\r\n", "a\nb\n").as_deref(),
+            Some("line endings differ (LF rendered, CRLF on disk)")
+        );
+    }
+
+    #[test]
+    fn content_changes_still_get_a_diff() {
+        assert_eq!(line_ending_note("a\nb\n", "a\r\nc\r\n"), None);
+        assert_eq!(line_ending_note("a\n", "a\n"), None);
+    }
+}
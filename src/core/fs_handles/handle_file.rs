@@ -1,7 +1,16 @@
+use crate::core::data_hold::data_struct::FileStatus;
 use std::{fs, io};
 use std::path::Path;
 
-pub fn handle_file(file_path: &str, content: &str) -> io::Result<()> {
+/// Write `content` (text or raw bytes) to `file_path`, creating parent directories as needed.
+/// A file whose bytes already match is left untouched and reported as unchanged.
+pub fn handle_file(file_path: &str, content: &[u8]) -> io::Result<FileStatus> {
+    let status = match fs::read(file_path) {
+        Ok(existing) if existing == content => return Ok(FileStatus::Unchanged),
+        Ok(_) => FileStatus::Updated,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => FileStatus::Created,
+        Err(_) => FileStatus::Updated,
+    };
 
     if let Err(e) = fs::remove_file(file_path) {
         if e.kind() != io::ErrorKind::NotFound {
@@ -15,5 +24,14 @@ pub fn handle_file(file_path: &str, content: &str) -> io::Result<()> {
 
     fs::write(file_path, content)?;
 
-    Ok(())
-}
\ No newline at end of file
+    Ok(status)
+}
+
+/// Remove a file, returning whether it existed
+pub fn remove_file_if_exists(file_path: &str) -> io::Result<bool> {
+    match fs::remove_file(file_path) {
+        Ok(_) => Ok(true),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e),
+    }
+}
@@ -0,0 +1,74 @@
+use std::fs;
+use std::path::Path;
+
+const SAMPLE_OUTPUT_DIR: &str = "generated";
+
+const SAMPLE_CONFIG: &str = r#"{
+  "alias": {
+    "@": "../generated"
+  }
+}
+"#;
+
+const SAMPLE_PARSER: &str = r#"// Single-file function: the returned string is written to `path`
+function constants(content) {
+    return content
+        .split(/\r?\n/)
+        .filter(line => line.trim().length > 0)
+        .map(line => {
+            const [key, value] = line.split(',');
+            return `export const ${key.trim()} = ${value.trim()};`;
+        })
+        .join('\n') + '\n';
+}
+
+constants.path = '@/constants.js';
+
+// Composed function: returns path/content pairs, one file per line
+function notes(content) {
+    return content
+        .split(/\r?\n/)
+        .filter(line => line.trim().length > 0)
+        .map(line => {
+            const [key, value] = line.split(',');
+            return [`@/notes/${key.trim()}.txt`, `${value.trim()}\n`];
+        });
+}
+
+notes.compose = true;
+"#;
+
+const SAMPLE_CONTENT: &str = "answer,42\ngreeting,\"hello\"\n";
+
+/// Write a sample rc.config.json, parser and content file into `dir`.
+/// Returns each file with whether it was written (false when it existed and `force` is off).
+pub fn scaffold_project(dir: &str, force: bool) -> Result<Vec<(String, bool)>, String> {
+    let dir = Path::new(dir);
+    fs::create_dir_all(dir)
+        .map_err(|e| format!("Cannot create directory '{}': {}", dir.display(), e))?;
+
+    let files = [
+        ("rc.config.json", SAMPLE_CONFIG),
+        ("parser.js", SAMPLE_PARSER),
+        ("content.txt", SAMPLE_CONTENT),
+    ];
+
+    // The sample alias target has to exist for the config to validate
+    let output_dir = dir.join(SAMPLE_OUTPUT_DIR);
+    fs::create_dir_all(&output_dir)
+        .map_err(|e| format!("Cannot create directory '{}': {}", output_dir.display(), e))?;
+
+    let mut written = Vec::new();
+    for (name, content) in files {
+        let path = dir.join(name);
+        let path_str = path.to_string_lossy().to_string();
+        if path.exists() && !force {
+            written.push((path_str, false));
+            continue;
+        }
+        fs::write(&path, content).map_err(|e| format!("Cannot write '{}': {}", path_str, e))?;
+        written.push((path_str, true));
+    }
+
+    Ok(written)
+}
@@ -1,31 +1,144 @@
-use crate::core::data_hold::data_struct::Config;
+use crate::core::data_hold::data_struct::{Config, ContentFiles, JobConfig};
 use crate::core::fs_handles::normalize_path::normalize_path;
-use std::fs;
-use std::path::PathBuf;
-
-pub fn load_config_from_file() -> Config {
-    let file_path = "rc.config.json";
-
-    let mut config: Config = Config { alias: None };
-
-    // if the file exists, try to load config
-    match fs::metadata(file_path) {
-        Ok(_) => {
-            let json_content = fs::read_to_string(file_path).unwrap();
-            let config_in_file = serde_json::from_str::<Config>(&json_content).unwrap();
-            let config_file_path = PathBuf::from(file_path).canonicalize().unwrap();
-            if let Some(mut alias_config) = config_in_file.alias {
-                for (_, value) in alias_config.iter_mut() {
-                    *value =
-                        normalize_path(&*config_file_path.join(PathBuf::from(value.to_string())))
-                            .to_string_lossy()
-                            .to_string();
-                }
-                config.alias = Some(alias_config);
+use crate::core::fs_handles::validate_config::{config_syntax_error, validate_config};
+use std::collections::HashMap;
+use std::{env, fs};
+use std::path::{Path, PathBuf};
+
+pub const CONFIG_FILE_NAME: &str = "rc.config.json";
+
+/// Locate the config file: the explicit `--config` path if given, otherwise the first
+/// rc.config.json found walking up from the parser's directory, then from the cwd
+pub fn discover_config_file(
+    explicit: Option<&str>,
+    parser_file: Option<&Path>,
+) -> Result<Option<PathBuf>, String> {
+    if let Some(path) = explicit {
+        return PathBuf::from(path)
+            .canonicalize()
+            .map(Some)
+            .map_err(|e| format!("Cannot find config file '{}': {}", path, e));
+    }
+
+    let mut search_roots = Vec::new();
+    if let Some(parser_dir) = parser_file.and_then(|p| p.parent()) {
+        search_roots.push(parser_dir.to_path_buf());
+    }
+    if let Ok(cwd) = env::current_dir() {
+        search_roots.push(cwd);
+    }
+
+    for root in search_roots {
+        for dir in root.ancestors() {
+            let candidate = dir.join(CONFIG_FILE_NAME);
+            if candidate.is_file() {
+                return Ok(Some(normalize_path(&candidate)));
             }
         }
-        Err(_) => {}
+    }
+
+    Ok(None)
+}
+
+/// Load the given config file, or an empty config (no aliases, no jobs) when there is none
+pub fn load_config_from_file(config_file: Option<&Path>) -> Result<Config, String> {
+    let mut config: Config = Config {
+        alias: None,
+        jobs: None,
+        libs: None,
+        path: None,
+        vars: Default::default(),
+        filter: Default::default(),
+    };
+
+    let Some(file_path) = config_file else {
+        return Ok(config);
+    };
+    let display_path = file_path.to_string_lossy().to_string();
+
+    let json_content = fs::read_to_string(file_path)
+        .map_err(|e| format!("Cannot read {}: {}", display_path, e))?;
+    let config_in_file = serde_json::from_str::<Config>(&json_content)
+        .map_err(|e| config_syntax_error(&display_path, &e))?;
+    let config_file_path = file_path
+        .canonicalize()
+        .map_err(|e| format!("Cannot resolve {}: {}", display_path, e))?;
+    validate_config(&json_content, &display_path, &config_in_file, &config_file_path)?;
+    let config_dir = config_file_path.parent().unwrap_or(Path::new("/"));
+    if let Some(mut alias_config) = config_in_file.alias {
+        resolve_alias_targets(&mut alias_config, &config_file_path);
+        config.alias = Some(alias_config);
+    }
+    if let Some(mut jobs) = config_in_file.jobs {
+        for job in jobs.iter_mut() {
+            resolve_job_paths(job, &config_file_path, config_dir);
+        }
+        config.jobs = Some(jobs);
+    }
+    if let Some(libs) = config_in_file.libs {
+        config.libs = Some(libs.iter().map(|dir| resolve_job_path(config_dir, dir)).collect());
+    }
+    config.path = Some(normalize_path(&config_file_path).to_string_lossy().to_string());
+
+    Ok(config)
+}
+
+fn resolve_alias_targets(alias_config: &mut HashMap<String, String>, config_file_path: &Path) {
+    for (_, value) in alias_config.iter_mut() {
+        *value = resolve_alias_target(config_file_path, value);
+    }
+}
+
+/// Alias targets are joined onto the config file path itself, so `"../out"` is a sibling of it
+pub(crate) fn resolve_alias_target(config_file_path: &Path, value: &str) -> String {
+    normalize_path(&config_file_path.join(PathBuf::from(value)))
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Job parser and content paths, like library directories, are relative to the config directory
+pub(crate) fn resolve_job_path(config_dir: &Path, path: &str) -> String {
+    normalize_path(&config_dir.join(path))
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Make a job's parser and content paths absolute (relative to the config directory)
+fn resolve_job_paths(job: &mut JobConfig, config_file_path: &Path, config_dir: &Path) {
+    job.parser = resolve_job_path(config_dir, &job.parser);
+    job.content = match &job.content {
+        ContentFiles::One(path) => ContentFiles::Many(vec![resolve_job_path(config_dir, path)]),
+        ContentFiles::Many(paths) => ContentFiles::Many(
+            paths.iter().map(|p| resolve_job_path(config_dir, p)).collect(),
+        ),
     };
+    if let Some(alias_config) = job.alias.as_mut() {
+        resolve_alias_targets(alias_config, config_file_path);
+    }
+}
+
+/// Name used to select a job: its explicit name, or the parser file stem
+pub fn job_name(job: &JobConfig) -> String {
+    job.name.clone().unwrap_or_else(|| {
+        Path::new(&job.parser)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default()
+    })
+}
 
-    config
+/// Build the config a job runs with: the global aliases overlaid with the job's own
+pub fn config_for_job(config: &Config, job: &JobConfig) -> Config {
+    let mut alias = config.alias.clone().unwrap_or_default();
+    if let Some(job_alias) = &job.alias {
+        alias.extend(job_alias.clone());
+    }
+    Config {
+        alias: Some(alias),
+        jobs: None,
+        libs: config.libs.clone(),
+        path: config.path.clone(),
+        vars: config.vars.clone(),
+        filter: config.filter.clone(),
+    }
 }
@@ -0,0 +1,32 @@
+use crate::core::fs_handles::validate_config::config_syntax_error;
+use serde_json::{Map, Value};
+use std::fs;
+
+/// Merge the `--vars` JSON object with the `--define key=value` pairs; defines win,
+/// and later defines override earlier ones. Defined values are always strings.
+pub fn load_vars(vars_file: Option<&str>, defines: &[String]) -> Result<Map<String, Value>, String> {
+    let mut vars = match vars_file {
+        Some(path) => {
+            let json = fs::read_to_string(path)
+                .map_err(|e| format!("Cannot read vars file '{}': {}", path, e))?;
+            match serde_json::from_str::<Value>(&json) {
+                Ok(Value::Object(map)) => map,
+                Ok(_) => return Err(format!("Vars file '{}' must contain a JSON object", path)),
+                Err(e) => return Err(config_syntax_error(path, &e)),
+            }
+        }
+        None => Map::new(),
+    };
+
+    for define in defines {
+        let (key, value) = define
+            .split_once('=')
+            .ok_or_else(|| format!("Invalid --define '{}', expected key=value", define))?;
+        if key.is_empty() {
+            return Err(format!("Invalid --define '{}', the key is empty", define));
+        }
+        vars.insert(key.to_string(), Value::String(value.to_string()));
+    }
+
+    Ok(vars)
+}
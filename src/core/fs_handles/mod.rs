@@ -1,4 +1,10 @@
 pub mod handle_file;
 pub mod normalize_path;
 pub mod replace_prefix_or_not;
-pub mod load_config_file;
\ No newline at end of file
+pub mod load_config_file;
+pub mod check_against_disk;
+pub mod watch_files;
+pub mod init_project;
+pub mod validate_config;
+pub mod sandbox_path;
+pub mod load_vars;
@@ -4,7 +4,7 @@ use std::collections::HashMap;
 pub fn replace_prefix_longest(s: &str, map: &HashMap<String, String>) -> Result<String, bool> {
     // Convert HashMap key-value pairs to a Vec and sort by key length in descending order (longest first)
     let mut sorted_pairs: Vec<_> = map.iter().collect();
-    sorted_pairs.sort_by(|a, b| b.0.len().cmp(&a.0.len()));
+    sorted_pairs.sort_by_key(|pair| std::cmp::Reverse(pair.0.len()));
 
     // Iterate over sorted key-value pairs to find prefix match
     for (prefix, replacement) in sorted_pairs {
@@ -0,0 +1,34 @@
+use crate::core::functions_handles::from_key_to_fs_path::from_string_to_path_string;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Resolve a path requested by a parser the same way output paths are resolved (alias prefix,
+/// otherwise relative to `base_dir`), then make sure it stays inside one of `allowed_roots`.
+/// Symlinks are followed before the check, so they cannot be used to escape.
+pub fn resolve_sandboxed_path(
+    requested: &str,
+    path_alias: &HashMap<String, String>,
+    base_dir: &Path,
+    allowed_roots: &[PathBuf],
+) -> Result<PathBuf, String> {
+    let resolved = from_string_to_path_string(path_alias, base_dir, requested.to_string());
+    let canonical = fs::canonicalize(&resolved)
+        .map_err(|e| format!("cannot access '{}': {}", resolved, e))?;
+    if allowed_roots.iter().any(|root| canonical.starts_with(root)) {
+        Ok(canonical)
+    } else {
+        Err(format!(
+            "'{}' is outside the project root and the alias targets",
+            resolved
+        ))
+    }
+}
+
+/// Canonical form of every root that exists; missing roots cannot contain anything anyway
+pub fn canonical_roots<'a>(roots: impl IntoIterator<Item = &'a Path>) -> Vec<PathBuf> {
+    roots
+        .into_iter()
+        .filter_map(|root| fs::canonicalize(root).ok())
+        .collect()
+}
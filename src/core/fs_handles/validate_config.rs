@@ -0,0 +1,129 @@
+use crate::core::data_hold::data_struct::{Config, ContentFiles};
+use crate::core::fs_handles::load_config_file::{resolve_alias_target, resolve_job_path};
+use std::path::Path;
+
+/// Turn a serde_json error into `file:line:column: message`.
+/// serde_json reports column 0 at the start of a line or at EOF; editors count from 1.
+pub fn config_syntax_error(display_path: &str, error: &serde_json::Error) -> String {
+    let message = error.to_string();
+    let message = match message.rsplit_once(" at line ") {
+        Some((head, _)) => head.to_string(),
+        None => message,
+    };
+    format!("{}:{}:{}: {}", display_path, error.line(), error.column().max(1), message)
+}
+
+/// Check what serde cannot: alias targets, library directories, job parsers and job content
+/// files must exist.
+/// Every problem is reported as `file:line:column: message`, one per line.
+pub fn validate_config(
+    source: &str,
+    display_path: &str,
+    config: &Config,
+    config_file_path: &Path,
+) -> Result<(), String> {
+    let config_dir = config_file_path.parent().unwrap_or(Path::new("/"));
+    let mut errors = Vec::new();
+
+    let check_alias = |key: &str, value: &str, errors: &mut Vec<String>| {
+        let target = resolve_alias_target(config_file_path, value);
+        if !Path::new(&target).exists() {
+            let (line, column) = locate_value(source, Some(key), value);
+            errors.push(format!(
+                "{}:{}:{}: alias '{}' points to '{}', which does not exist",
+                display_path, line, column, key, target
+            ));
+        }
+    };
+
+    for (key, value) in config.alias.iter().flatten() {
+        check_alias(key, value, &mut errors);
+    }
+
+    for lib in config.libs.iter().flatten() {
+        let lib_dir = resolve_job_path(config_dir, lib);
+        if !Path::new(&lib_dir).is_dir() {
+            let (line, column) = locate_value(source, None, lib);
+            errors.push(format!(
+                "{}:{}:{}: library directory '{}' does not exist",
+                display_path, line, column, lib_dir
+            ));
+        }
+    }
+
+    for job in config.jobs.iter().flatten() {
+        let parser = resolve_job_path(config_dir, &job.parser);
+        if !Path::new(&parser).is_file() {
+            let (line, column) = locate_value(source, Some("parser"), &job.parser);
+            errors.push(format!(
+                "{}:{}:{}: job parser '{}' does not exist",
+                display_path, line, column, parser
+            ));
+        }
+
+        let content_files = match &job.content {
+            ContentFiles::One(path) => std::slice::from_ref(path),
+            ContentFiles::Many(paths) => paths.as_slice(),
+        };
+        if content_files.is_empty() {
+            let (line, column) = locate_key(source, "content");
+            errors.push(format!(
+                "{}:{}:{}: job content must list at least one file",
+                display_path, line, column
+            ));
+        }
+        for content in content_files {
+            let content_path = resolve_job_path(config_dir, content);
+            if !Path::new(&content_path).is_file() {
+                let (line, column) = locate_value(source, None, content);
+                errors.push(format!(
+                    "{}:{}:{}: job content file '{}' does not exist",
+                    display_path, line, column, content_path
+                ));
+            }
+        }
+
+        for (key, value) in job.alias.iter().flatten() {
+            check_alias(key, value, &mut errors);
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("\n"))
+    }
+}
+
+/// 1-based line and column of the first `"key": "value"` pair (or of the bare `"value"`
+/// string when `key` is None), falling back to the start of the file
+fn locate_value(source: &str, key: Option<&str>, value: &str) -> (usize, usize) {
+    let quoted_value = serde_json::to_string(value).unwrap_or_default();
+    let offset = match key {
+        Some(key) => {
+            let quoted_key = serde_json::to_string(key).unwrap_or_default();
+            source.match_indices(&quoted_key).find_map(|(index, _)| {
+                let rest = &source[index + quoted_key.len()..];
+                let after_colon = rest.trim_start().strip_prefix(':')?;
+                let value_start = after_colon.trim_start();
+                value_start
+                    .starts_with(&quoted_value)
+                    .then(|| source.len() - value_start.len())
+            })
+        }
+        None => source.find(&quoted_value),
+    };
+    line_column(source, offset.unwrap_or(0))
+}
+
+fn locate_key(source: &str, key: &str) -> (usize, usize) {
+    let quoted_key = serde_json::to_string(key).unwrap_or_default();
+    line_column(source, source.find(&quoted_key).unwrap_or(0))
+}
+
+pub(crate) fn line_column(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+    (line, column)
+}
@@ -0,0 +1,33 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// Call `on_change` once at start and again whenever any path it returned last time is created,
+/// modified or removed, polling their modification times. `on_change` returns the paths to watch
+/// next, so files discovered by a run (such as new imports) are watched from then on.
+/// Runs until the process is stopped.
+pub fn watch_files<F: FnMut() -> Vec<PathBuf>>(interval: Duration, mut on_change: F) -> ! {
+    let snapshot = |paths: &[PathBuf]| -> HashMap<PathBuf, Option<SystemTime>> {
+        paths
+            .iter()
+            .map(|path| {
+                let modified = fs::metadata(path).and_then(|m| m.modified()).ok();
+                (path.clone(), modified)
+            })
+            .collect()
+    };
+
+    let mut paths = on_change();
+    let mut last_seen = snapshot(&paths);
+
+    loop {
+        thread::sleep(interval);
+        let current = snapshot(&paths);
+        if current != last_seen {
+            paths = on_change();
+            last_seen = snapshot(&paths);
+        }
+    }
+}
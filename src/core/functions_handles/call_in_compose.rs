@@ -1,40 +1,39 @@
 use crate::core::boa_js_specific::js_convert_judge::{
-    extract_string, is_array, js_value_to_string,
+    binary_content, extract_file_content, is_array, js_value_to_string,
 };
+use crate::core::boa_js_specific::settle_promise::settle_promise;
 use crate::core::data_hold::data_struct::FunctionReturnType;
 use crate::core::functions_handles::from_key_to_fs_path::{
     from_js_value_to_path_string, from_string_to_path_string,
 };
-use boa_engine::{Context, JsString, JsValue};
+use boa_engine::{Context, JsObject, JsString, JsValue};
 use std::collections::HashMap;
 use std::path::Path;
 
 /// Call function if it's composed, normalize and modify function returns for fs writing
 pub fn call_function_typed(
     context: &mut Context,
+    functions: &JsObject,
     func_name: &str,
-    content: &str,
+    args: &[JsValue],
     path_alias: &HashMap<String, String>,
     current_path: &Path,
 ) -> Result<FunctionReturnType, String> {
-    let global = context.global_object();
     let func_key = JsString::from(func_name);
 
     // Get the function
-    let func = global
+    let func = functions
         .get(func_key, context)
         .map_err(|e| format!("Failed to get function: {}", e))?;
 
-    // Call the function
-    let content_value = JsValue::from(JsString::from(content));
-    let args = [content_value];
-
+    // Call the function with the content, vars and earlier outputs
     let result = match func.as_callable() {
         Some(callable) => callable
-            .call(&JsValue::undefined(), &args, context)
+            .call(&JsValue::undefined(), args, context)
             .map_err(|e| format!("{}", e))?,
         None => return Err(format!("{} is not callable", func_name)),
     };
+    let result = settle_promise(context, result)?;
 
     // Determine return type
     classify_result(context, &result, path_alias, current_path)
@@ -47,8 +46,12 @@ fn classify_result(
     path_alias: &HashMap<String, String>,
     current_path: &Path,
 ) -> Result<FunctionReturnType, String> {
+    // Uint8Array, ArrayBuffer or {base64}: a single binary file
+    if let Some(bytes) = binary_content(context, result)? {
+        Ok(FunctionReturnType::Binary(bytes))
+    }
     // Check if it's an array using Array.isArray()
-    if is_array(context, result) {
+    else if is_array(context, result) {
         // Parse as 2D array
         parse_2d_array(context, result, path_alias, current_path)
     } else if result.is_object() && !result.is_null() {
@@ -97,35 +100,21 @@ fn parse_2d_array(
                 .map_err(|e| format!("Failed to get value at index {}: {}", i, e))?;
 
             let input_key = from_js_value_to_path_string(path_alias, current_path, key);
-            let value_str = extract_string(&value);
+            let value_content = extract_file_content(context, &value)?;
 
-            result.push((input_key, value_str));
+            result.push((input_key, value_content));
         }
-        // Check if item is an object
+        // Check if item is an object (possibly a nested directory tree)
         else if item.is_object() && !item.is_null() {
             let item_obj = item.as_object().ok_or("Item is not an object")?;
+            let mut files = Vec::new();
+            flatten_tree(context, item_obj, "", &mut files)
+                .map_err(|e| format!("Item {}: {}", i, e))?;
 
-            // Get all keys from the object
-            let keys = item_obj
-                .own_property_keys(context)
-                .map_err(|e| format!("Failed to get keys at index {}: {}", i, e))?;
-
-            // Process each key-value pair in the object
-            for key in keys {
-                let key_name = match &key {
-                    boa_engine::property::PropertyKey::String(s) => s.to_std_string_escaped(),
-                    boa_engine::property::PropertyKey::Index(idx) => idx.get().to_string(),
-                    boa_engine::property::PropertyKey::Symbol(_) => continue,
-                };
-
-                let value = item_obj
-                    .get(key, context)
-                    .map_err(|e| format!("Failed to get value for key {}: {}", key_name, e))?;
-
+            for (key_name, value) in files {
                 let key_input = from_string_to_path_string(path_alias, current_path, key_name);
-
-                let value_str = extract_string(&value);
-                result.push((key_input, value_str));
+                let value_content = extract_file_content(context, &value)?;
+                result.push((key_input, value_content));
             }
         } else {
             return Err(format!("Item {} is neither an array nor an object", i));
@@ -135,7 +124,7 @@ fn parse_2d_array(
     Ok(FunctionReturnType::Array2D(result))
 }
 
-/// Parse object into key-value pairs
+/// Parse object into key-value pairs; nested objects are directories
 fn parse_object(
     context: &mut Context,
     obj: &JsValue,
@@ -144,12 +133,35 @@ fn parse_object(
 ) -> Result<FunctionReturnType, String> {
     let obj_ref = obj.as_object().ok_or("Not an object")?;
 
-    let keys = obj_ref
-        .own_property_keys(context)
-        .map_err(|e| format!("Failed to get keys: {}", e))?;
+    let mut files = Vec::new();
+    flatten_tree(context, obj_ref, "", &mut files)?;
 
     let mut result = Vec::new();
 
+    for (key_name, value) in files {
+        let value_content = extract_file_content(context, &value)?;
+        // The alias can only match the root key, since the whole joined path is resolved
+        let key_input = from_string_to_path_string(path_alias, current_path, key_name);
+
+        result.push((key_input, value_content));
+    }
+
+    Ok(FunctionReturnType::Object(result))
+}
+
+/// Flatten a directory tree such as `{"@": {"src": {"App.java": "..."}}, "README.md": "..."}`
+/// into `("@/src/App.java", value)` pairs. Plain objects are directories; strings, arrays,
+/// binary values (`Uint8Array`, `ArrayBuffer`, `{base64}`) and anything else are file contents.
+fn flatten_tree(
+    context: &mut Context,
+    tree: &JsObject,
+    prefix: &str,
+    files: &mut Vec<(String, JsValue)>,
+) -> Result<(), String> {
+    let keys = tree
+        .own_property_keys(context)
+        .map_err(|e| format!("Failed to get keys: {}", e))?;
+
     for key in keys {
         let key_name = match &key {
             boa_engine::property::PropertyKey::String(s) => s.to_std_string_escaped(),
@@ -157,15 +169,27 @@ fn parse_object(
             boa_engine::property::PropertyKey::Symbol(_) => continue,
         };
 
-        let value = obj_ref
+        let value = tree
             .get(key, context)
-            .map_err(|e| format!("Failed to get value: {}", e))?;
+            .map_err(|e| format!("Failed to get value for key {}: {}", key_name, e))?;
 
-        let value_str = extract_string(&value);
-        let key_input = from_string_to_path_string(path_alias, current_path, key_name);
+        let path = if prefix.is_empty() {
+            key_name
+        } else {
+            format!("{}/{}", prefix.trim_end_matches('/'), key_name.trim_start_matches('/'))
+        };
 
-        result.push((key_input, value_str));
+        match value.as_object() {
+            Some(directory)
+                if !directory.is_callable()
+                    && !is_array(context, &value)
+                    && binary_content(context, &value)?.is_none() =>
+            {
+                flatten_tree(context, directory, &path, files)?;
+            }
+            _ => files.push((path, value)),
+        }
     }
 
-    Ok(FunctionReturnType::Object(result))
+    Ok(())
 }
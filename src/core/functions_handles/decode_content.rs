@@ -0,0 +1,281 @@
+use crate::core::fs_handles::validate_config::{config_syntax_error, line_column};
+use serde_json::{Map, Number, Value};
+use std::path::Path;
+
+/// Formats a function may ask for with `fn.input`
+pub const INPUT_FORMATS: [&str; 5] = ["json", "jsonc", "yaml", "toml", "csv"];
+
+/// The format named by `fn.input`; `"auto"` picks it from the content file's extension,
+/// so content read from stdin (`-`) needs an explicit format
+pub fn input_format(requested: &str, content_file: &str) -> Result<String, String> {
+    if requested != "auto" {
+        return if INPUT_FORMATS.contains(&requested) {
+            Ok(requested.to_string())
+        } else {
+            Err(format!(
+                "unknown input format '{}', expected {} or auto",
+                requested,
+                INPUT_FORMATS.join(", ")
+            ))
+        };
+    }
+
+    if content_file == "-" {
+        return Err(format!(
+            "fn.input = \"auto\" cannot infer a format for content read from stdin, set fn.input to one of {}",
+            INPUT_FORMATS.join(", ")
+        ));
+    }
+    let extension = Path::new(content_file)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "json" => Ok("json".to_string()),
+        "jsonc" | "json5" => Ok("jsonc".to_string()),
+        "yaml" | "yml" => Ok("yaml".to_string()),
+        "toml" => Ok("toml".to_string()),
+        "csv" => Ok("csv".to_string()),
+        _ => Err(format!(
+            "cannot infer an input format from '{}', set fn.input to one of {}",
+            content_file,
+            INPUT_FORMATS.join(", ")
+        )),
+    }
+}
+
+/// Parse the content in the given format. Errors read `file:line:column: message`.
+pub fn decode_content(format: &str, content: &str, content_file: &str) -> Result<Value, String> {
+    match format {
+        "json" => serde_json::from_str(content).map_err(|e| config_syntax_error(content_file, &e)),
+        "jsonc" => serde_json::from_str(&strip_jsonc(content))
+            .map_err(|e| config_syntax_error(content_file, &e)),
+        "yaml" => serde_yaml::from_str(content).map_err(|e| {
+            let (line, column) = e
+                .location()
+                .map(|location| (location.line(), location.column()))
+                .unwrap_or((1, 1));
+            located(content_file, line, column, &e.to_string())
+        }),
+        "toml" => match content.parse::<toml::Table>() {
+            Ok(table) => Ok(toml_to_json(toml::Value::Table(table))),
+            Err(e) => {
+                let offset = e.span().map(|span| span.start).unwrap_or(0);
+                let (line, column) = line_column(content, offset);
+                Err(located(content_file, line, column, e.message()))
+            }
+        },
+        "csv" => decode_csv(content, content_file),
+        _ => Err(format!("unknown input format '{}'", format)),
+    }
+}
+
+/// `file:line:column: message`, without the parser's own " at line .." suffix
+fn located(content_file: &str, line: usize, column: usize, message: &str) -> String {
+    let message = match message.split_once(" at line ") {
+        Some((head, _)) => head,
+        None => message,
+    };
+    let message: Vec<&str> = message.lines().map(str::trim).filter(|l| !l.is_empty()).collect();
+    format!("{}:{}:{}: {}", content_file, line, column, message.join("; "))
+}
+
+/// CSV with a header row becomes an array of objects keyed by the headers (all values strings)
+fn decode_csv(content: &str, content_file: &str) -> Result<Value, String> {
+    let mut reader = csv::Reader::from_reader(content.as_bytes());
+    let csv_error = |e: csv::Error| {
+        let line = e.position().map(|position| position.line() as usize).unwrap_or(1);
+        located(content_file, line, 1, &e.to_string())
+    };
+
+    let headers = reader.headers().map_err(csv_error)?.clone();
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(csv_error)?;
+        let row: Map<String, Value> = headers
+            .iter()
+            .zip(record.iter())
+            .map(|(header, field)| (header.to_string(), Value::String(field.to_string())))
+            .collect();
+        rows.push(Value::Object(row));
+    }
+    Ok(Value::Array(rows))
+}
+
+fn toml_to_json(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(s) => Value::String(s),
+        toml::Value::Integer(i) => Value::Number(i.into()),
+        toml::Value::Float(f) => Number::from_f64(f).map(Value::Number).unwrap_or(Value::Null),
+        toml::Value::Boolean(b) => Value::Bool(b),
+        toml::Value::Datetime(datetime) => Value::String(datetime.to_string()),
+        toml::Value::Array(items) => Value::Array(items.into_iter().map(toml_to_json).collect()),
+        toml::Value::Table(table) => Value::Object(
+            table
+                .into_iter()
+                .map(|(key, value)| (key, toml_to_json(value)))
+                .collect(),
+        ),
+    }
+}
+
+/// Blank out `//` and `/* */` comments and trailing commas, keeping every newline and
+/// character position so JSON errors still point at the right line and column
+fn strip_jsonc(source: &str) -> String {
+    let chars: Vec<char> = source.chars().collect();
+    let mut out: Vec<char> = Vec::with_capacity(chars.len());
+    let mut i = 0;
+    let mut in_string = false;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if in_string {
+            out.push(c);
+            if c == '\\' && i + 1 < chars.len() {
+                out.push(chars[i + 1]);
+                i += 1;
+            } else if c == '"' {
+                in_string = false;
+            }
+            i += 1;
+            continue;
+        }
+
+        match (c, chars.get(i + 1)) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+                i += 1;
+            }
+            ('/', Some('/')) => {
+                while i < chars.len() && chars[i] != '\n' {
+                    out.push(' ');
+                    i += 1;
+                }
+            }
+            ('/', Some('*')) => {
+                let mut closed = false;
+                while i < chars.len() {
+                    if chars[i] == '*' && chars.get(i + 1) == Some(&'/') {
+                        out.push(' ');
+                        out.push(' ');
+                        i += 2;
+                        closed = true;
+                        break;
+                    }
+                    out.push(if chars[i] == '\n' { '\n' } else { ' ' });
+                    i += 1;
+                }
+                if !closed {
+                    break;
+                }
+            }
+            (']' | '}', _) => {
+                // Drop a comma separated from this bracket only by whitespace (or comments)
+                if let Some(comma) = out.iter().rposition(|ch| !ch.is_whitespace()) {
+                    if out[comma] == ',' {
+                        out[comma] = ' ';
+                    }
+                }
+                out.push(c);
+                i += 1;
+            }
+            _ => {
+                out.push(c);
+                i += 1;
+            }
+        }
+    }
+
+    out.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(value: &Value) -> Vec<&str> {
+        value.as_object().unwrap().keys().map(String::as_str).collect()
+    }
+
+    #[test]
+    fn objects_keep_source_key_order() {
+        let json = decode_content("json", r#"{"zeta": 1, "alpha": 2, "mid": 3}"#, "c.json").unwrap();
+        assert_eq!(keys(&json), ["zeta", "alpha", "mid"]);
+
+        let jsonc = decode_content("jsonc", "{\"zeta\": 1, // z\n\"alpha\": 2,}", "c.jsonc").unwrap();
+        assert_eq!(keys(&jsonc), ["zeta", "alpha"]);
+
+        let yaml = decode_content("yaml", "zeta: 1\nalpha: 2\nmid: 3\n", "c.yaml").unwrap();
+        assert_eq!(keys(&yaml), ["zeta", "alpha", "mid"]);
+
+        let toml = decode_content("toml", "zeta = 1\nalpha = 2\n[mid]\ny = 1\nb = 2\n", "c.toml").unwrap();
+        assert_eq!(keys(&toml), ["zeta", "alpha", "mid"]);
+        assert_eq!(keys(&toml["mid"]), ["y", "b"]);
+    }
+
+    #[test]
+    fn auto_format_comes_from_the_extension() {
+        assert_eq!(input_format("auto", "shorts.YML").unwrap(), "yaml");
+        assert_eq!(input_format("auto", "a/b.json5").unwrap(), "jsonc");
+        assert_eq!(input_format("csv", "-").unwrap(), "csv");
+        assert!(input_format("auto", "-").unwrap_err().contains("stdin"));
+        assert!(input_format("auto", "shorts.jsd").unwrap_err().starts_with("cannot infer"));
+        assert!(input_format("xml", "a.xml").unwrap_err().starts_with("unknown input format"));
+    }
+
+    #[test]
+    fn csv_rows_keep_header_order() {
+        let rows = decode_content("csv", "name,age,city\nAda,36,London\n", "c.csv").unwrap();
+        assert_eq!(keys(&rows[0]), ["name", "age", "city"]);
+        assert_eq!(rows[0]["city"], "London");
+    }
+
+    #[test]
+    fn jsonc_comments_are_blanked_outside_strings() {
+        let source = "{\n  // line\n  \"url\": \"http://x/*y*/\", /* block\n  spans */ \"n\": 1\n}";
+        let value = decode_content("jsonc", source, "c.jsonc").unwrap();
+        assert_eq!(value["url"], "http://x/*y*/");
+        assert_eq!(value["n"], 1);
+    }
+
+    #[test]
+    fn jsonc_escaped_quotes_do_not_end_strings() {
+        let source = r#"{"a": "say \"hi\" // not a comment", "b": "\\"} // trailing"#;
+        let value = decode_content("jsonc", source, "c.jsonc").unwrap();
+        assert_eq!(value["a"], "say \"hi\" // not a comment");
+        assert_eq!(value["b"], "\\");
+    }
+
+    #[test]
+    fn jsonc_trailing_commas_are_dropped() {
+        let source = "{\"list\": [1, 2, /* x */ ],\n \"obj\": {\"a\": 1,\n // end\n },}";
+        let value = decode_content("jsonc", source, "c.jsonc").unwrap();
+        assert_eq!(value["list"], serde_json::json!([1, 2]));
+        assert_eq!(value["obj"]["a"], 1);
+        // Commas inside strings stay
+        let value = decode_content("jsonc", r#"{"s": ",]"}"#, "c.jsonc").unwrap();
+        assert_eq!(value["s"], ",]");
+    }
+
+    #[test]
+    fn jsonc_keeps_line_and_column_positions() {
+        let stripped = strip_jsonc("{ /* a\nb */ \"k\": 1 // c\n}");
+        assert_eq!(stripped.lines().count(), 3);
+        assert_eq!(stripped.len(), "{ /* a\nb */ \"k\": 1 // c\n}".len());
+
+        let source = "{\n  /* one\n     two */ // three\n  \"a\": 1,\n  \"b\": 1 2\n}";
+        let error = decode_content("jsonc", source, "c.jsonc").unwrap_err();
+        assert!(error.starts_with("c.jsonc:5:10:"), "{}", error);
+    }
+
+    #[test]
+    fn errors_are_positioned_for_every_format() {
+        assert!(decode_content("json", "{\n  \"a\": }", "c.json").unwrap_err().starts_with("c.json:2:"));
+        // serde_json says column 0 at EOF
+        assert!(decode_content("json", "{\n", "c.json").unwrap_err().starts_with("c.json:2:1: EOF"));
+        assert!(decode_content("yaml", "a: 1\nb: [\n", "c.yaml").unwrap_err().starts_with("c.yaml:"));
+        assert!(decode_content("toml", "a = 1\nb = \n", "c.toml").unwrap_err().starts_with("c.toml:2:"));
+        assert!(decode_content("csv", "a,b\n1,2\n3\n", "c.csv").unwrap_err().starts_with("c.csv:3:"));
+    }
+}
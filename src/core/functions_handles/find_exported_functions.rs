@@ -1,20 +1,24 @@
 use crate::core::boa_js_specific::built_in_filter::is_builtin;
-use crate::core::data_hold::data_struct::FunctionForExecute;
-use crate::core::boa_js_specific::get_property::{get_property, has_property};
+use crate::core::data_hold::data_struct::{FilterArgs, FunctionForExecute, SkipReason};
+use crate::core::boa_js_specific::get_property::{
+    get_flag, get_property, get_string_list, has_callable_property, has_property,
+};
 use boa_engine::property::PropertyKey;
 use boa_engine::{Context, JsObject};
 
+/// Collect the callable properties of `functions` (the global object of a script parser,
+/// or the exports of a module parser) that pass `filter`
 pub fn filter_functions_inside<F>(
     context: &mut Context,
+    functions: &JsObject,
     filter: F,
 ) -> Result<Vec<FunctionForExecute>, String>
 where
     F: Fn(&JsObject, &mut Context, &mut FunctionForExecute) -> bool,
 {
-    let global = context.global_object();
     let mut exported = Vec::new();
 
-    let keys = global
+    let keys = functions
         .own_property_keys(context)
         .map_err(|e| format!("Failed to get property keys: {}", e))?;
 
@@ -31,16 +35,25 @@ where
             continue;
         }
 
-        let value = global
+        let value = functions
             .get(key, context)
             .map_err(|e| format!("Failed to get value: {}", e))?;
 
         if value.is_callable() {
             let value_obj = value.as_object().unwrap();
 
+            let input = get_property(value_obj, "input", context);
+            let description = get_property(value_obj, "description", context);
             let mut current_value = FunctionForExecute {
                 path: "".to_string(),
+                computed_path: false,
                 function_name: name,
+                input: (!input.is_empty()).then_some(input),
+                after: get_string_list(value_obj, "after", context),
+                description: (!description.is_empty()).then_some(description),
+                enabled: get_flag(value_obj, "enabled", true, context),
+                tags: get_string_list(value_obj, "tags", context),
+                conditional: has_callable_property(value_obj, "when", context),
             };
 
             let can_push = filter(value_obj, context, &mut current_value);
@@ -55,23 +68,36 @@ where
     Ok(exported)
 }
 
-pub fn find_exported_functions(context: &mut Context) -> Result<Vec<FunctionForExecute>, String> {
-    Ok(filter_functions_inside(
+pub fn find_exported_functions(
+    context: &mut Context,
+    functions: &JsObject,
+) -> Result<Vec<FunctionForExecute>, String> {
+    filter_functions_inside(
         context,
+        functions,
         |value_obj, context, current_value| {
             let get_property = get_property(value_obj, "path", context);
             if !get_property.is_empty() {
                 current_value.path = get_property.to_string();
                 return true;
             }
+            // `fn.path = (content, meta) => "..."`
+            if has_callable_property(value_obj, "path", context) {
+                current_value.computed_path = true;
+                return true;
+            }
             false
         },
-    )?)
+    )
 }
 
-pub fn find_composed_functions(context: &mut Context) -> Result<Vec<FunctionForExecute>, String> {
-    Ok(filter_functions_inside(
+pub fn find_composed_functions(
+    context: &mut Context,
+    functions: &JsObject,
+) -> Result<Vec<FunctionForExecute>, String> {
+    filter_functions_inside(
         context,
+        functions,
         |value_obj, context, current_value| {
             let get_property = has_property(value_obj, "compose", context);
             if get_property {
@@ -80,5 +106,24 @@ pub fn find_composed_functions(context: &mut Context) -> Result<Vec<FunctionForE
             }
             false
         },
-    )?)
+    )
+}
+
+/// Why a function does not run, or `None` when it does: it must match an `--only` entry when
+/// there are any, must not be disabled with `fn.enabled = false`, and must match no `--skip`
+/// entry. Entries match a name or a tag. `--only` is checked first, so when every function is
+/// `NotOnly` no entry matched anything.
+pub fn skip_reason(function: &FunctionForExecute, filter: &FilterArgs) -> Option<SkipReason> {
+    let matches = |selector: &String| {
+        function.function_name == *selector || function.tags.contains(selector)
+    };
+    if !filter.only.is_empty() && !filter.only.iter().any(matches) {
+        Some(SkipReason::NotOnly)
+    } else if !function.enabled {
+        Some(SkipReason::Disabled)
+    } else if filter.skip.iter().any(matches) {
+        Some(SkipReason::Skip)
+    } else {
+        None
+    }
 }
@@ -11,7 +11,7 @@ pub fn from_js_value_to_path_string(
     key_path: JsValue,
 ) -> String {
     let key_extracted = extract_string(&key_path);
-    let key_str = match replace_prefix_longest(&*key_extracted, path_alias) {
+    let key_str = match replace_prefix_longest(&key_extracted, path_alias) {
         Ok(path) => PathBuf::from(path),
         Err(_) => current_path.join(key_extracted),
     };
@@ -23,7 +23,7 @@ pub fn from_string_to_path_string(
     current_path: &Path,
     key_path: String,
 ) -> String {
-    let key_str = match replace_prefix_longest(&*key_path, path_alias) {
+    let key_str = match replace_prefix_longest(&key_path, path_alias) {
         Ok(path) => PathBuf::from(path),
         Err(_) => current_path.join(key_path),
     };
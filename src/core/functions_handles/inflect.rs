@@ -0,0 +1,327 @@
+/// Words that are the same in singular and plural
+const UNCOUNTABLE: [&str; 17] = [
+    "data",
+    "deer",
+    "equipment",
+    "evidence",
+    "feedback",
+    "fish",
+    "hardware",
+    "information",
+    "metadata",
+    "money",
+    "news",
+    "rice",
+    "series",
+    "sheep",
+    "software",
+    "species",
+    "staff",
+];
+
+/// (singular, plural) pairs the suffix rules below would get wrong
+const IRREGULAR: [(&str, &str); 47] = [
+    ("person", "people"),
+    ("child", "children"),
+    ("man", "men"),
+    ("woman", "women"),
+    ("mouse", "mice"),
+    ("goose", "geese"),
+    ("tooth", "teeth"),
+    ("foot", "feet"),
+    ("ox", "oxen"),
+    ("criterion", "criteria"),
+    ("quiz", "quizzes"),
+    ("index", "indices"),
+    ("matrix", "matrices"),
+    ("vertex", "vertices"),
+    ("analysis", "analyses"),
+    ("basis", "bases"),
+    ("crisis", "crises"),
+    ("diagnosis", "diagnoses"),
+    ("hypothesis", "hypotheses"),
+    ("thesis", "theses"),
+    ("calf", "calves"),
+    ("half", "halves"),
+    ("knife", "knives"),
+    ("leaf", "leaves"),
+    ("life", "lives"),
+    ("loaf", "loaves"),
+    ("self", "selves"),
+    ("shelf", "shelves"),
+    ("thief", "thieves"),
+    ("wife", "wives"),
+    ("wolf", "wolves"),
+    ("echo", "echoes"),
+    ("hero", "heroes"),
+    ("potato", "potatoes"),
+    ("tomato", "tomatoes"),
+    ("veto", "vetoes"),
+    ("cookie", "cookies"),
+    ("movie", "movies"),
+    ("tie", "ties"),
+    ("cache", "caches"),
+    ("niche", "niches"),
+    ("alias", "aliases"),
+    ("bonus", "bonuses"),
+    ("bus", "buses"),
+    ("campus", "campuses"),
+    ("status", "statuses"),
+    ("virus", "viruses"),
+];
+
+/// Split an identifier or phrase into words: `StudentList`, `student_list`, `student-list`
+/// and `student list` all give `["Student"/"student", "List"/"list"]`. An acronym ends
+/// before its last capital when a lowercase letter follows (`HTTPServer` → `HTTP`, `Server`).
+pub fn split_words(text: &str) -> Vec<&str> {
+    word_spans(text)
+        .into_iter()
+        .map(|(start, end)| &text[start..end])
+        .collect()
+}
+
+/// Byte ranges of the words found by `split_words`
+fn word_spans(text: &str) -> Vec<(usize, usize)> {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let mut spans = Vec::new();
+    let mut start: Option<usize> = None;
+
+    for (i, &(offset, c)) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if let Some(begin) = start.take() {
+                spans.push((begin, offset));
+            }
+            continue;
+        }
+        if let Some(begin) = start {
+            let previous = chars[i - 1].1;
+            let next_is_lower = chars.get(i + 1).is_some_and(|&(_, next)| next.is_lowercase());
+            let boundary = c.is_uppercase()
+                && (previous.is_lowercase()
+                    || previous.is_numeric()
+                    || (previous.is_uppercase() && next_is_lower));
+            if boundary {
+                spans.push((begin, offset));
+                start = Some(offset);
+            }
+        } else {
+            start = Some(offset);
+        }
+    }
+    if let Some(begin) = start {
+        spans.push((begin, text.len()));
+    }
+    spans
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars.flat_map(char::to_lowercase)).collect(),
+        None => String::new(),
+    }
+}
+
+/// `student_list`
+pub fn snake_case(text: &str) -> String {
+    split_words(text)
+        .iter()
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// `student-list`
+pub fn kebab_case(text: &str) -> String {
+    split_words(text)
+        .iter()
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// `STUDENT_LIST`
+pub fn constant_case(text: &str) -> String {
+    split_words(text)
+        .iter()
+        .map(|word| word.to_uppercase())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// `StudentList`
+pub fn pascal_case(text: &str) -> String {
+    split_words(text).iter().map(|word| capitalize(word)).collect()
+}
+
+/// `studentList`
+pub fn camel_case(text: &str) -> String {
+    split_words(text)
+        .iter()
+        .enumerate()
+        .map(|(i, word)| if i == 0 { word.to_lowercase() } else { capitalize(word) })
+        .collect()
+}
+
+/// `Student list`
+pub fn humanize(text: &str) -> String {
+    let sentence = split_words(text)
+        .iter()
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<_>>()
+        .join(" ");
+    capitalize(&sentence)
+}
+
+/// Plural of the last word, keeping everything else as written: `StudentCategory` →
+/// `StudentCategories`, `person` → `people`, `BOX` → `BOXES`
+pub fn plural(text: &str) -> String {
+    inflect_last_word(text, plural_word)
+}
+
+/// Singular of the last word, keeping everything else as written: `StudentCategories` →
+/// `StudentCategory`, `people` → `person`
+pub fn singular(text: &str) -> String {
+    inflect_last_word(text, singular_word)
+}
+
+fn inflect_last_word(text: &str, inflect: fn(&str) -> String) -> String {
+    match word_spans(text).last() {
+        Some(&(start, end)) => format!("{}{}{}", &text[..start], inflect(&text[start..end]), &text[end..]),
+        None => text.to_string(),
+    }
+}
+
+fn plural_word(word: &str) -> String {
+    let lower = word.to_lowercase();
+    if UNCOUNTABLE.contains(&lower.as_str()) || IRREGULAR.iter().any(|(_, many)| *many == lower) {
+        return word.to_string();
+    }
+    if let Some((_, many)) = IRREGULAR.iter().find(|(one, _)| *one == lower) {
+        return match_case(word, many);
+    }
+
+    let consonant_y = lower.ends_with('y')
+        && !lower[..lower.len() - 1].ends_with(['a', 'e', 'i', 'o', 'u']);
+    let (strip, suffix) = if ["s", "x", "z", "ch", "sh"].iter().any(|end| lower.ends_with(end)) {
+        (0, "es")
+    } else if consonant_y {
+        (1, "ies")
+    } else {
+        (0, "s")
+    };
+    replace_suffix(word, strip, suffix)
+}
+
+fn singular_word(word: &str) -> String {
+    let lower = word.to_lowercase();
+    if UNCOUNTABLE.contains(&lower.as_str()) || IRREGULAR.iter().any(|(one, _)| *one == lower) {
+        return word.to_string();
+    }
+    if let Some((one, _)) = IRREGULAR.iter().find(|(_, many)| *many == lower) {
+        return match_case(word, one);
+    }
+
+    let strip_es = ["sses", "xes", "zzes", "ches", "shes"];
+    let (strip, suffix) = if lower.len() > 3 && lower.ends_with("ies") {
+        (3, "y")
+    } else if strip_es.iter().any(|end| lower.ends_with(end)) {
+        (2, "")
+    } else if ["ss", "us", "is"].iter().any(|end| lower.ends_with(end)) {
+        (0, "")
+    } else if lower.ends_with('s') {
+        (1, "")
+    } else {
+        (0, "")
+    };
+    replace_suffix(word, strip, suffix)
+}
+
+/// Drop `strip` trailing ASCII letters and append `suffix`, upper-cased for an all-caps word
+fn replace_suffix(word: &str, strip: usize, suffix: &str) -> String {
+    let stem = &word[..word.len() - strip];
+    if is_all_caps(word) {
+        format!("{}{}", stem, suffix.to_uppercase())
+    } else {
+        format!("{}{}", stem, suffix)
+    }
+}
+
+/// `replacement` (lowercase) written in the case of `word`: `PERSON`, `Person` or `person`
+fn match_case(word: &str, replacement: &str) -> String {
+    if is_all_caps(word) {
+        replacement.to_uppercase()
+    } else if word.starts_with(char::is_uppercase) {
+        capitalize(replacement)
+    } else {
+        replacement.to_string()
+    }
+}
+
+fn is_all_caps(word: &str) -> bool {
+    word.chars().count() > 1 && !word.chars().any(char::is_lowercase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_identifiers_and_acronyms() {
+        assert_eq!(split_words("StudentList"), ["Student", "List"]);
+        assert_eq!(split_words("student_list"), ["student", "list"]);
+        assert_eq!(split_words("student-list item"), ["student", "list", "item"]);
+        assert_eq!(split_words("HTTPServer"), ["HTTP", "Server"]);
+        assert_eq!(split_words("parseJSON2Xml"), ["parse", "JSON2", "Xml"]);
+        assert!(split_words("__").is_empty());
+    }
+
+    #[test]
+    fn converts_between_cases() {
+        assert_eq!(snake_case("HTTPServer"), "http_server");
+        assert_eq!(snake_case("StudentList"), "student_list");
+        assert_eq!(kebab_case("StudentList"), "student-list");
+        assert_eq!(constant_case("studentList"), "STUDENT_LIST");
+        assert_eq!(pascal_case("student_list"), "StudentList");
+        assert_eq!(camel_case("student-list"), "studentList");
+        assert_eq!(camel_case("HTTPServer"), "httpServer");
+        assert_eq!(humanize("studentList"), "Student list");
+    }
+
+    #[test]
+    fn pluralizes_the_last_word() {
+        assert_eq!(plural("UserCategory"), "UserCategories");
+        assert_eq!(plural("person"), "people");
+        assert_eq!(plural("Student"), "Students");
+        assert_eq!(plural("BOX"), "BOXES");
+        assert_eq!(plural("day"), "days");
+        assert_eq!(plural("branch"), "branches");
+        assert_eq!(plural("user_address"), "user_addresses");
+        assert_eq!(plural("Person"), "People");
+        assert_eq!(plural("knife"), "knives");
+        assert_eq!(plural("data"), "data");
+        assert_eq!(plural("people"), "people");
+        assert_eq!(kebab_case(&plural("Student")) + "-list", "students-list");
+    }
+
+    #[test]
+    fn singularizes_the_last_word() {
+        assert_eq!(singular("UserCategories"), "UserCategory");
+        assert_eq!(singular("people"), "person");
+        assert_eq!(singular("Students"), "Student");
+        assert_eq!(singular("boxes"), "box");
+        assert_eq!(singular("addresses"), "address");
+        assert_eq!(singular("status"), "status");
+        assert_eq!(singular("analyses"), "analysis");
+        assert_eq!(singular("series"), "series");
+        assert_eq!(singular("person"), "person");
+    }
+
+    #[test]
+    fn irregular_pairs_round_trip() {
+        for (one, many) in IRREGULAR {
+            assert_eq!(plural(one), many);
+            assert_eq!(singular(many), one);
+        }
+    }
+}
@@ -1,5 +1,8 @@
 pub mod find_exported_functions;
 pub mod call_in_compose;
+pub mod decode_content;
 pub mod from_key_to_fs_path;
+pub mod inflect;
 pub(crate) mod make_uuid_for_path_not;
+pub mod render_template;
 pub mod write_fs_kv_pairs;
\ No newline at end of file
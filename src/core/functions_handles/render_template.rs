@@ -0,0 +1,553 @@
+use crate::core::fs_handles::validate_config::line_column;
+use serde_json::Value;
+
+/// Partials may include partials, but not forever
+const MAX_PARTIAL_DEPTH: usize = 32;
+
+/// Reads a partial by name and returns its display name and source
+pub type PartialLoader<'a> = dyn FnMut(&str) -> Result<(String, String), String> + 'a;
+
+/// Render a logic-less Mustache/Handlebars-style template. Nothing is HTML-escaped, since the
+/// output is source code. Supported tags:
+/// `{{name}}`, `{{a.b}}`, `{{.}}` / `{{this}}`, `{{{name}}}` and `{{& name}}` (same as `{{name}}`),
+/// `{{#name}}..{{/name}}` (list: once per item, object: once inside it, falsy: skipped),
+/// `{{^name}}..{{/name}}`, `{{#each list}}`, `{{#if x}}`, `{{#unless x}}`, `{{#with x}}`,
+/// `{{else}}` inside any block, `{{@index}}` / `{{@first}}` / `{{@last}}` / `{{@key}}` in loops,
+/// `{{! comment}}` and `{{> partial}}`.
+/// A block or comment tag alone on its line removes the whole line from the output; a partial
+/// alone on its line is indented like the tag.
+pub fn render_template(
+    source: &str,
+    template_name: &str,
+    data: &Value,
+    load_partial: &mut PartialLoader,
+) -> Result<String, String> {
+    let nodes = parse_template(source, template_name)?;
+    let mut renderer = Renderer {
+        load_partial,
+        depth: 0,
+    };
+    let mut scopes = vec![Scope::new(data.clone())];
+    let mut out = String::new();
+    renderer.render(&nodes, &mut scopes, &mut out)?;
+    Ok(out)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BlockKind {
+    Section,
+    Inverted,
+    Each,
+    If,
+    Unless,
+    With,
+}
+
+#[derive(Debug)]
+enum Node {
+    Text(String),
+    Variable(String),
+    Partial(String, String),
+    Block {
+        kind: BlockKind,
+        name: String,
+        children: Vec<Node>,
+        otherwise: Vec<Node>,
+    },
+}
+
+#[derive(Debug)]
+enum Tag<'a> {
+    Text(&'a str),
+    Variable(&'a str),
+    Open(BlockKind, &'a str, &'a str),
+    Close(&'a str),
+    Else,
+    Comment,
+    Partial(&'a str, &'a str),
+}
+
+impl Tag<'_> {
+    /// Tags that produce no output and may stand alone on a line
+    fn is_standalone_kind(&self) -> bool {
+        matches!(self, Tag::Open(..) | Tag::Close(_) | Tag::Else | Tag::Comment | Tag::Partial(..))
+    }
+}
+
+/// `file:line:column: message`
+fn template_error(source: &str, template_name: &str, offset: usize, message: &str) -> String {
+    let (line, column) = line_column(source, offset);
+    format!("{}:{}:{}: {}", template_name, line, column, message)
+}
+
+/// What the text inside `{{ }}` means
+fn classify(inner: &str) -> Result<Tag<'_>, String> {
+    let inner = inner.trim();
+    let rest = |prefix: usize| inner[prefix..].trim();
+    match inner.chars().next() {
+        None => Err("empty tag".to_string()),
+        Some('!') => Ok(Tag::Comment),
+        Some('&') => Ok(Tag::Variable(rest(1))),
+        Some('>') => Ok(Tag::Partial(rest(1), "")),
+        Some('/') => Ok(Tag::Close(rest(1))),
+        Some('^') => Ok(Tag::Open(BlockKind::Inverted, rest(1), rest(1))),
+        Some('#') => {
+            let body = rest(1);
+            match body.split_once(char::is_whitespace) {
+                None => Ok(Tag::Open(BlockKind::Section, body, body)),
+                Some((helper, argument)) => {
+                    let kind = match helper {
+                        "each" => BlockKind::Each,
+                        "if" => BlockKind::If,
+                        "unless" => BlockKind::Unless,
+                        "with" => BlockKind::With,
+                        _ => return Err(format!("unknown block helper '{}'", helper)),
+                    };
+                    Ok(Tag::Open(kind, helper, argument.trim()))
+                }
+            }
+        }
+        _ if inner == "else" => Ok(Tag::Else),
+        _ => Ok(Tag::Variable(inner)),
+    }
+}
+
+/// Split the source into text and tags, with their byte offsets
+fn tokenize<'a>(source: &'a str, template_name: &str) -> Result<Vec<(Tag<'a>, usize)>, String> {
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+
+    while let Some(found) = source[pos..].find("{{") {
+        let open = pos + found;
+        let triple = source[open..].starts_with("{{{");
+        let (inner_start, closer) = if triple { (open + 3, "}}}") } else { (open + 2, "}}") };
+        let inner_len = source[inner_start..]
+            .find(closer)
+            .ok_or_else(|| template_error(source, template_name, open, "unclosed tag"))?;
+        let inner = &source[inner_start..inner_start + inner_len];
+        let mut close = inner_start + inner_len + closer.len();
+        let mut tag = if triple {
+            Tag::Variable(inner.trim())
+        } else {
+            classify(inner).map_err(|e| template_error(source, template_name, open, &e))?
+        };
+
+        // A standalone block, comment or partial tag takes its whole line with it
+        let mut text_end = open;
+        if tag.is_standalone_kind() {
+            let line_start = source[..open].rfind('\n').map_or(0, |i| i + 1);
+            let rest = &source[close..];
+            let line_end = rest.find('\n').map_or(rest.len(), |i| i + 1);
+            if line_start >= pos
+                && source[line_start..open].trim().is_empty()
+                && rest[..line_end].trim().is_empty()
+            {
+                text_end = line_start;
+                close += line_end;
+                if let Tag::Partial(_, indent) = &mut tag {
+                    *indent = &source[line_start..open];
+                }
+            }
+        }
+
+        if text_end > pos {
+            tokens.push((Tag::Text(&source[pos..text_end]), pos));
+        }
+        tokens.push((tag, open));
+        pos = close;
+    }
+    if pos < source.len() {
+        tokens.push((Tag::Text(&source[pos..]), pos));
+    }
+    Ok(tokens)
+}
+
+/// An open block while parsing: its kind, closing name, argument, offset and collected nodes
+struct OpenBlock {
+    kind: BlockKind,
+    close_name: String,
+    name: String,
+    offset: usize,
+    children: Vec<Node>,
+    otherwise: Option<Vec<Node>>,
+}
+
+fn parse_template(source: &str, template_name: &str) -> Result<Vec<Node>, String> {
+    let mut root = Vec::new();
+    let mut open_blocks: Vec<OpenBlock> = Vec::new();
+
+    for (tag, offset) in tokenize(source, template_name)? {
+        let node = match tag {
+            Tag::Text(text) => Node::Text(text.to_string()),
+            Tag::Variable(name) => Node::Variable(name.to_string()),
+            Tag::Partial(name, indent) => Node::Partial(name.to_string(), indent.to_string()),
+            Tag::Comment => continue,
+            Tag::Open(kind, close_name, name) => {
+                open_blocks.push(OpenBlock {
+                    kind,
+                    close_name: close_name.to_string(),
+                    name: name.to_string(),
+                    offset,
+                    children: Vec::new(),
+                    otherwise: None,
+                });
+                continue;
+            }
+            Tag::Else => {
+                match open_blocks.last_mut() {
+                    Some(block) if block.otherwise.is_none() => block.otherwise = Some(Vec::new()),
+                    _ => {
+                        return Err(template_error(source, template_name, offset, "unexpected {{else}}"))
+                    }
+                }
+                continue;
+            }
+            Tag::Close(name) => {
+                let block = open_blocks.pop().ok_or_else(|| {
+                    let message = format!("'{{{{/{}}}}}' closes nothing", name);
+                    template_error(source, template_name, offset, &message)
+                })?;
+                if block.close_name != name {
+                    let message = format!(
+                        "'{{{{/{}}}}}' does not close '{}' opened at line {}",
+                        name,
+                        block.close_name,
+                        line_column(source, block.offset).0
+                    );
+                    return Err(template_error(source, template_name, offset, &message));
+                }
+                Node::Block {
+                    kind: block.kind,
+                    name: block.name,
+                    children: block.children,
+                    otherwise: block.otherwise.unwrap_or_default(),
+                }
+            }
+        };
+
+        let siblings = match open_blocks.last_mut() {
+            Some(block) => block.otherwise.as_mut().unwrap_or(&mut block.children),
+            None => &mut root,
+        };
+        siblings.push(node);
+    }
+
+    if let Some(block) = open_blocks.last() {
+        let message = format!("unclosed section '{}'", block.close_name);
+        return Err(template_error(source, template_name, block.offset, &message));
+    }
+    Ok(root)
+}
+
+/// One level of the name lookup stack, with loop information when it is a list item
+struct Scope {
+    value: Value,
+    index: Option<(usize, usize)>,
+    key: Option<String>,
+}
+
+impl Scope {
+    fn new(value: Value) -> Self {
+        Scope {
+            value,
+            index: None,
+            key: None,
+        }
+    }
+}
+
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64().is_some_and(|n| n != 0.0),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(_) => true,
+    }
+}
+
+fn to_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Resolve `name` against the scopes, innermost first; missing names are `None`
+fn lookup(scopes: &[Scope], name: &str) -> Option<Value> {
+    let current = scopes.last()?;
+    let loop_scope = || scopes.iter().rev().find(|scope| scope.index.is_some());
+    match name {
+        "." | "this" => return Some(current.value.clone()),
+        "@index" => return loop_scope().and_then(|s| s.index).map(|(i, _)| Value::from(i)),
+        "@first" => return loop_scope().and_then(|s| s.index).map(|(i, _)| Value::Bool(i == 0)),
+        "@last" => {
+            return loop_scope()
+                .and_then(|s| s.index)
+                .map(|(i, len)| Value::Bool(i + 1 == len))
+        }
+        "@key" => return loop_scope().and_then(|s| s.key.clone()).map(Value::String),
+        _ => {}
+    }
+
+    let (first, rest) = match name.strip_prefix("this.") {
+        Some(path) => (None, path),
+        None => {
+            let (first, rest) = name.split_once('.').unwrap_or((name, ""));
+            (Some(first), rest)
+        }
+    };
+    let mut value = match first {
+        Some(first) => scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.value.as_object().and_then(|object| object.get(first)))?,
+        None => &current.value,
+    };
+    for segment in rest.split('.').filter(|segment| !segment.is_empty()) {
+        value = match value {
+            Value::Object(object) => object.get(segment)?,
+            Value::Array(items) => items.get(segment.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    Some(value.clone())
+}
+
+struct Renderer<'a, 'b> {
+    load_partial: &'a mut PartialLoader<'b>,
+    depth: usize,
+}
+
+impl Renderer<'_, '_> {
+    fn render(&mut self, nodes: &[Node], scopes: &mut Vec<Scope>, out: &mut String) -> Result<(), String> {
+        for node in nodes {
+            match node {
+                Node::Text(text) => out.push_str(text),
+                Node::Variable(name) => {
+                    if let Some(value) = lookup(scopes, name) {
+                        out.push_str(&to_text(&value));
+                    }
+                }
+                Node::Partial(name, indent) => {
+                    if self.depth >= MAX_PARTIAL_DEPTH {
+                        return Err(format!("partial '{}' is nested too deeply", name));
+                    }
+                    let (partial_name, source) = (self.load_partial)(name)?;
+                    let partial = parse_template(&source, &partial_name)?;
+                    let mut rendered = String::new();
+                    self.depth += 1;
+                    let result = self.render(&partial, scopes, &mut rendered);
+                    self.depth -= 1;
+                    result?;
+                    for line in rendered.split_inclusive('\n') {
+                        out.push_str(indent);
+                        out.push_str(line);
+                    }
+                }
+                Node::Block {
+                    kind,
+                    name,
+                    children,
+                    otherwise,
+                } => self.render_block(*kind, name, children, otherwise, scopes, out)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn render_block(
+        &mut self,
+        kind: BlockKind,
+        name: &str,
+        children: &[Node],
+        otherwise: &[Node],
+        scopes: &mut Vec<Scope>,
+        out: &mut String,
+    ) -> Result<(), String> {
+        let value = lookup(scopes, name).unwrap_or(Value::Null);
+        let truthy = is_truthy(&value);
+
+        match (kind, value) {
+            (BlockKind::If, _) | (BlockKind::Unless, _) => {
+                let show = truthy == (kind == BlockKind::If);
+                self.render(if show { children } else { otherwise }, scopes, out)
+            }
+            (BlockKind::Inverted, _) => self.render(if truthy { otherwise } else { children }, scopes, out),
+            (_, _) if !truthy => self.render(otherwise, scopes, out),
+            (BlockKind::Section | BlockKind::Each, Value::Array(items)) => {
+                let len = items.len();
+                for (i, item) in items.into_iter().enumerate() {
+                    let scope = Scope {
+                        value: item,
+                        index: Some((i, len)),
+                        key: None,
+                    };
+                    self.render_in(scope, children, scopes, out)?;
+                }
+                Ok(())
+            }
+            (BlockKind::Each, Value::Object(entries)) => {
+                let len = entries.len();
+                for (i, (key, item)) in entries.into_iter().enumerate() {
+                    let scope = Scope {
+                        value: item,
+                        index: Some((i, len)),
+                        key: Some(key),
+                    };
+                    self.render_in(scope, children, scopes, out)?;
+                }
+                Ok(())
+            }
+            (_, value) => self.render_in(Scope::new(value), children, scopes, out),
+        }
+    }
+
+    fn render_in(
+        &mut self,
+        scope: Scope,
+        nodes: &[Node],
+        scopes: &mut Vec<Scope>,
+        out: &mut String,
+    ) -> Result<(), String> {
+        scopes.push(scope);
+        let result = self.render(nodes, scopes, out);
+        scopes.pop();
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn render(source: &str, data: Value) -> Result<String, String> {
+        render_template(source, "t.tpl", &data, &mut |name: &str| Err(format!("no partial '{}'", name)))
+    }
+
+    fn render_with_partials(source: &str, data: Value, partials: &[(&str, &str)]) -> Result<String, String> {
+        render_template(source, "t.tpl", &data, &mut |name: &str| {
+            partials
+                .iter()
+                .find(|(partial, _)| *partial == name)
+                .map(|(partial, source)| (format!("{}.tpl", partial), source.to_string()))
+                .ok_or_else(|| format!("no partial '{}'", name))
+        })
+    }
+
+    #[test]
+    fn variables_are_not_escaped() {
+        let data = json!({"name": "<List>", "user": {"id": 7}, "items": ["a", "b"], "none": null});
+        let out = render("{{name}} {{{name}}} {{& name}} {{user.id}} {{items.1}} [{{none}}] [{{missing}}]", data);
+        assert_eq!(out.unwrap(), "<List> <List> <List> 7 b [] []");
+    }
+
+    #[test]
+    fn sections_repeat_enter_or_skip() {
+        let data = json!({"list": [{"n": 1}, {"n": 2}], "user": {"name": "Ada"}, "empty": [], "off": false});
+        let out = render("{{#list}}{{n}},{{/list}} {{#user}}{{name}}{{/user}} {{#empty}}x{{/empty}}{{#off}}y{{/off}}.", data);
+        assert_eq!(out.unwrap(), "1,2, Ada .");
+    }
+
+    #[test]
+    fn inverted_sections_render_for_falsy_values() {
+        let data = json!({"empty": [], "list": [1], "zero": 0, "blank": ""});
+        let out = render("{{^empty}}a{{/empty}}{{^list}}b{{/list}}{{^zero}}c{{/zero}}{{^blank}}d{{/blank}}{{^missing}}e{{/missing}}", data);
+        assert_eq!(out.unwrap(), "acde");
+    }
+
+    #[test]
+    fn each_exposes_loop_variables() {
+        let data = json!({"fields": ["id", "name", "age"]});
+        let out = render("{{#each fields}}{{@index}}={{this}}{{#if @first}}(first){{/if}}{{#unless @last}}, {{/unless}}{{/each}}", data);
+        assert_eq!(out.unwrap(), "0=id(first), 1=name, 2=age");
+    }
+
+    #[test]
+    fn each_walks_objects_in_insertion_order() {
+        let data = json!({"fields": {"zeta": "Long", "alpha": "String", "mid": "int"}});
+        let out = render("{{#each fields}}{{@key}}:{{.}} {{/each}}", data).unwrap();
+        assert_eq!(out, "zeta:Long alpha:String mid:int ");
+    }
+
+    #[test]
+    fn each_else_renders_for_empty_lists() {
+        let out = render("{{#each items}}{{.}}{{else}}none{{/each}}", json!({"items": []}));
+        assert_eq!(out.unwrap(), "none");
+    }
+
+    #[test]
+    fn if_unless_and_else() {
+        let template = "{{#if admin}}admin{{else}}user{{/if}} {{#unless admin}}limited{{else}}full{{/unless}}";
+        assert_eq!(render(template, json!({"admin": true})).unwrap(), "admin full");
+        assert_eq!(render(template, json!({"admin": false})).unwrap(), "user limited");
+    }
+
+    #[test]
+    fn with_enters_an_object_and_parent_names_stay_visible() {
+        let data = json!({"package": "com.acme", "entity": {"name": "Student"}});
+        let out = render("{{#with entity}}{{package}}.{{name}}{{/with}}{{#with missing}}x{{else}}-{{/with}}", data);
+        assert_eq!(out.unwrap(), "com.acme.Student-");
+    }
+
+    #[test]
+    fn comments_render_nothing() {
+        assert_eq!(render("a{{! note }}b", json!({})).unwrap(), "ab");
+    }
+
+    #[test]
+    fn standalone_tags_remove_their_lines() {
+        let template = "class A {\n  {{#each fields}}\n  int {{.}};\n  {{/each}}\n  {{! end }}\n}\n";
+        let out = render(template, json!({"fields": ["a", "b"]}));
+        assert_eq!(out.unwrap(), "class A {\n  int a;\n  int b;\n}\n");
+    }
+
+    #[test]
+    fn inline_tags_keep_their_lines() {
+        let out = render("a {{#if x}}b{{/if}} c\n", json!({"x": true}));
+        assert_eq!(out.unwrap(), "a b c\n");
+    }
+
+    #[test]
+    fn partials_share_the_scope_and_indent_standalone_lines() {
+        let partials = [("field", "private {{type}} {{name}};\n")];
+        let template = "class A {\n{{#each fields}}\n    {{> field}}\n{{/each}}\n}\n";
+        let data = json!({"fields": [{"type": "int", "name": "id"}, {"type": "String", "name": "name"}]});
+        let out = render_with_partials(template, data, &partials);
+        assert_eq!(out.unwrap(), "class A {\n    private int id;\n    private String name;\n}\n");
+    }
+
+    #[test]
+    fn partials_cannot_nest_forever() {
+        let error = render_with_partials("{{> loop}}", json!({}), &[("loop", "{{> loop}}")]).unwrap_err();
+        assert_eq!(error, "partial 'loop' is nested too deeply");
+    }
+
+    #[test]
+    fn missing_partials_report_the_loader_error() {
+        assert_eq!(render("{{> nope}}", json!({})).unwrap_err(), "no partial 'nope'");
+    }
+
+    #[test]
+    fn errors_point_at_the_offending_tag() {
+        assert_eq!(render("ok\n  {{name", json!({})).unwrap_err(), "t.tpl:2:3: unclosed tag");
+        assert_eq!(render("a\n{{}}", json!({})).unwrap_err(), "t.tpl:2:1: empty tag");
+        assert_eq!(
+            render("{{#each a}}\n{{/if}}", json!({})).unwrap_err(),
+            "t.tpl:2:1: '{{/if}}' does not close 'each' opened at line 1"
+        );
+        assert_eq!(render("x {{/a}}", json!({})).unwrap_err(), "t.tpl:1:3: '{{/a}}' closes nothing");
+        assert_eq!(render("\n\n {{#a}}", json!({})).unwrap_err(), "t.tpl:3:2: unclosed section 'a'");
+        assert_eq!(render("{{else}}", json!({})).unwrap_err(), "t.tpl:1:1: unexpected {{else}}");
+        assert_eq!(render("{{#loop x}}", json!({})).unwrap_err(), "t.tpl:1:1: unknown block helper 'loop'");
+    }
+
+    #[test]
+    fn partial_errors_name_the_partial_file() {
+        let error = render_with_partials("a\n{{> bad}}", json!({}), &[("bad", "\n{{#x}}")]).unwrap_err();
+        assert_eq!(error, "bad.tpl:2:1: unclosed section 'x'");
+    }
+}
@@ -1,27 +1,382 @@
 pub(crate) mod core;
 
-use self::core::data_hold::data_struct::Args;
-use crate::core::executors::execute_exported_functions;
-use self::core::fs_handles::load_config_file::load_config_from_file;
-use self::core::console_print::print_console::{print_content_required, print_js_required};
-use clap::Parser;
+use self::core::data_hold::config_schema::config_schema;
+use self::core::data_hold::exit_code;
+use self::core::data_hold::data_struct::{
+    Args, CheckArgs, Command, Config, ContentFiles, FilterArgs, InitArgs, InputArgs, JobsArgs, ParserArgs,
+    ReportFormat, RunArgs, RunMode, RunReport, SkipReason, SkippedFunction, VarsArgs,
+};
+use crate::core::executors::{
+    check_exported_functions, clean_exported_functions, execute_exported_functions,
+    list_exported_functions, parser_sources, render_exported_functions,
+};
+use self::core::fs_handles::init_project::scaffold_project;
+use self::core::fs_handles::load_vars::load_vars;
+use self::core::fs_handles::load_config_file::{
+    config_for_job, discover_config_file, job_name, load_config_from_file, CONFIG_FILE_NAME,
+};
+use self::core::fs_handles::watch_files::watch_files;
+use self::core::console_print::run_report::{add_check_results, parser_failure, write_report};
+use self::core::console_print::print_console::{
+    print_check_report, print_clean_report, print_config_source, print_content_required,
+    print_dry_run_plan,
+    print_failures_to_stderr, print_function_list, print_init_report, print_js_required,
+    print_rendered_to_stdout, print_watch_report,
+};
+use clap::{Parser, ValueEnum};
 use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 /// JavaScript Function Evaluator - Execute exported functions with content from fil
-
 fn main() {
     let args = Args::parse();
 
-    // Get file paths from either flags or positional arguments
-    let js_file = args.file.or_else(|| args.positional.get(0).cloned());
-    let content_file = args.content.or_else(|| args.positional.get(1).cloned());
+    let config_arg = args.config.as_deref();
+
+    let exit_code = match &args.command {
+        Some(Command::Init(init_args)) => init_command(init_args),
+        Some(Command::Run(run_args)) => run_command(run_args, config_arg),
+        Some(Command::Check(check_args)) => check_command(check_args, config_arg),
+        Some(Command::List(parser_args)) => list_command(parser_args, config_arg),
+        Some(Command::Clean(input)) => clean_command(input, config_arg),
+        Some(Command::Jobs(jobs_args)) => jobs_command(jobs_args, config_arg),
+        Some(Command::Schema) => schema_command(),
+        None => run_command(&args.run, config_arg),
+    };
+
+    std::process::exit(exit_code);
+}
+
+fn init_command(init_args: &InitArgs) -> i32 {
+    match scaffold_project(&init_args.dir, init_args.force) {
+        Ok(files) => {
+            print_init_report(&files);
+            exit_code::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("❌ Error: {}", e);
+            exit_code::USAGE_ERROR
+        }
+    }
+}
+
+fn run_command(run_args: &RunArgs, config_arg: Option<&str>) -> i32 {
+    let report_target = match parse_report_target(&run_args.report) {
+        Ok(target) => target,
+        Err(code) => return code,
+    };
+
+    if !run_args.watch {
+        let mode = if run_args.dry_run {
+            RunMode::DryRun
+        } else if run_args.stdout {
+            RunMode::Stdout
+        } else {
+            RunMode::Write
+        };
+        return input_command(&run_args.input, mode, &report_target, config_arg);
+    }
+
+    let (js_file, js_file_str, content_file) = match resolve_input_paths(&run_args.input) {
+        Ok(paths) => paths,
+        Err(code) => return code,
+    };
+
+    if content_file == STDIN_PATH {
+        eprintln!("Error: --watch needs a content file, stdin cannot be watched");
+        return exit_code::USAGE_ERROR;
+    }
+
+    let config_file = match discover_config_file(config_arg, Some(Path::new(&js_file_str))) {
+        Ok(found) => found.unwrap_or_else(|| PathBuf::from(CONFIG_FILE_NAME)),
+        Err(e) => {
+            eprintln!("❌ Error: {}", e);
+            return exit_code::CONFIG_INVALID;
+        }
+    };
+
+    let vars_args = &run_args.input.vars;
+    let mut inputs = vec![
+        PathBuf::from(&js_file_str),
+        PathBuf::from(&content_file),
+        config_file,
+    ];
+    inputs.extend(vars_args.vars_file.as_ref().map(PathBuf::from));
+    let mut run_count = 0;
+    watch_files(Duration::from_millis(300), || {
+        run_count += 1;
+        // Imported modules and `libs` files, found again after every run
+        let mut sources = Vec::new();
+        let mut config_path = None;
+        let mut warnings = Vec::new();
+        let mut skipped = Vec::new();
+        let result = read_inputs(&js_file, &content_file).and_then(|(js_code, content)| {
+            let config_file = discover_config_file(config_arg, Some(Path::new(&js_file_str)))?;
+            let mut generation_config = load_config_from_file(config_file.as_deref())?;
+            generation_config.vars =
+                load_vars(vars_args.vars_file.as_deref(), &vars_args.defines)?;
+            generation_config.filter = run_args.input.filter.clone();
+            config_path = generation_config.path.clone();
+            sources = parser_sources(&js_code, &js_file_str, &generation_config);
+            execute_exported_functions(
+                &js_code,
+                &content,
+                &content_file,
+                &js_file_str,
+                &generation_config,
+                &mut warnings,
+                &mut skipped,
+            )
+        });
+        print_watch_report(run_count, &result);
+        let (successes, failures) = match result {
+            Ok(results) => results,
+            Err(e) => (Vec::new(), vec![parser_failure(&js_file_str, &e)]),
+        };
+        let report = RunReport {
+            config: config_path,
+            successes,
+            failures,
+            skipped,
+            warnings,
+        };
+        save_report(&report_target, &report);
+
+        let watched: Vec<PathBuf> = inputs.iter().cloned().chain(sources).collect();
+        if run_count == 1 {
+            println!(" 👀 Watching {} file(s), press Ctrl+C to stop\n", watched.len());
+        }
+        watched
+    });
+}
+
+fn check_command(check_args: &CheckArgs, config_arg: Option<&str>) -> i32 {
+    let report_target = match parse_report_target(&check_args.report) {
+        Ok(target) => target,
+        Err(code) => return code,
+    };
+    input_command(&check_args.input, RunMode::Check, &report_target, config_arg)
+}
+
+/// Resolve the parser and content files, read them and run once in `mode`
+fn input_command(
+    input: &InputArgs,
+    mode: RunMode,
+    report_target: &Option<(ReportFormat, String)>,
+    config_arg: Option<&str>,
+) -> i32 {
+    let (js_file, js_file_str, content_file) = match resolve_input_paths(input) {
+        Ok(paths) => paths,
+        Err(code) => return code,
+    };
+
+    let (js_code, content) = match read_inputs(&js_file, &content_file) {
+        Ok(inputs) => inputs,
+        Err(e) => {
+            eprintln!("{}", e);
+            return exit_code::USAGE_ERROR;
+        }
+    };
+
+    let parser_file = Some(Path::new(&js_file_str));
+    let generation_config = match load_run_config(config_arg, parser_file, &input.vars, &input.filter) {
+        Ok(config) => config,
+        Err(code) => return code,
+    };
+
+    let mut report = RunReport::default();
+    let code = run_once(mode, &js_code, &content, &content_file, &js_file_str, &generation_config, &mut report);
+    first_failure(code, save_report(report_target, &report))
+}
+
+fn list_command(parser_args: &ParserArgs, config_arg: Option<&str>) -> i32 {
+    // Absolute like `run`, so config discovery and its error locations match
+    let js_file_str = match fs::canonicalize(&parser_args.file) {
+        Ok(abs_path) => abs_path.to_string_lossy().to_string(),
+        Err(e) => {
+            eprintln!("Error: Cannot find JavaScript file '{}': {}", parser_args.file, e);
+            return exit_code::USAGE_ERROR;
+        }
+    };
+    let js_code = match fs::read_to_string(&js_file_str) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("Error reading JavaScript file '{}': {}", parser_args.file, e);
+            return exit_code::USAGE_ERROR;
+        }
+    };
+
+    // Needed for `libs`, which script parsers may call at the top level
+    let generation_config = match load_config(config_arg, Some(Path::new(&js_file_str))) {
+        Ok(config) => config,
+        Err(code) => return code,
+    };
+
+    match list_exported_functions(&js_code, &js_file_str, &generation_config) {
+        Ok((exported, composed)) => {
+            print_function_list(&exported, &composed);
+            if exported.is_empty() && composed.is_empty() {
+                exit_code::NO_FUNCTIONS
+            } else {
+                exit_code::SUCCESS
+            }
+        }
+        Err(e) => {
+            eprintln!("❌ Error: {}", e);
+            exit_code::JS_EVALUATION_FAILED
+        }
+    }
+}
+
+fn clean_command(input: &InputArgs, config_arg: Option<&str>) -> i32 {
+    let (js_file, js_file_str, content_file) = match resolve_input_paths(input) {
+        Ok(paths) => paths,
+        Err(code) => return code,
+    };
+
+    let (js_code, content) = match read_inputs(&js_file, &content_file) {
+        Ok(inputs) => inputs,
+        Err(e) => {
+            eprintln!("{}", e);
+            return exit_code::USAGE_ERROR;
+        }
+    };
+
+    let parser_file = Some(Path::new(&js_file_str));
+    let generation_config = match load_run_config(config_arg, parser_file, &input.vars, &input.filter) {
+        Ok(config) => config,
+        Err(code) => return code,
+    };
+
+    let mut skipped = Vec::new();
+    match clean_exported_functions(&js_code, &content, &content_file, &js_file_str, &generation_config, &mut skipped) {
+        Ok((removed, failures)) => {
+            print_clean_report(&removed, &failures);
+            if failures.is_empty() {
+                exit_code::SUCCESS
+            } else {
+                exit_code::FUNCTIONS_FAILED
+            }
+        }
+        Err(e) => {
+            eprintln!("❌ Error: {}", e);
+            exit_code::JS_EVALUATION_FAILED
+        }
+    }
+}
+
+fn jobs_command(jobs_args: &JobsArgs, config_arg: Option<&str>) -> i32 {
+    let mode = if jobs_args.check {
+        RunMode::Check
+    } else if jobs_args.dry_run {
+        RunMode::DryRun
+    } else {
+        RunMode::Write
+    };
+    let report_target = match parse_report_target(&jobs_args.report) {
+        Ok(target) => target,
+        Err(code) => return code,
+    };
+    let generation_config = match load_run_config(config_arg, None, &jobs_args.vars, &jobs_args.filter) {
+        Ok(config) => config,
+        Err(code) => return code,
+    };
+    let mut report = RunReport::default();
+    let code = run_jobs(mode, &jobs_args.names, &generation_config, &mut report);
+    first_failure(code, save_report(&report_target, &report))
+}
+
+/// Find and load rc.config.json, reporting a missing or broken config with its own exit code
+fn load_config(config_arg: Option<&str>, parser_file: Option<&Path>) -> Result<Config, i32> {
+    discover_config_file(config_arg, parser_file)
+        .and_then(|config_file| load_config_from_file(config_file.as_deref()))
+        .map_err(|e| {
+            eprintln!("❌ Error: {}", e);
+            exit_code::CONFIG_INVALID
+        })
+}
+
+/// Load rc.config.json and attach the `--vars`/`--define` variables and the `--only`/`--skip`
+/// filters given on the command line
+fn load_run_config(
+    config_arg: Option<&str>,
+    parser_file: Option<&Path>,
+    vars_args: &VarsArgs,
+    filter: &FilterArgs,
+) -> Result<Config, i32> {
+    let mut config = load_config(config_arg, parser_file)?;
+    config.filter = filter.clone();
+    config.vars = load_vars(vars_args.vars_file.as_deref(), &vars_args.defines).map_err(|e| {
+        eprintln!("❌ Error: {}", e);
+        exit_code::USAGE_ERROR
+    })?;
+    Ok(config)
+}
+
+/// Keep the earlier failure code, so the first thing that went wrong decides the exit status
+fn first_failure(code: i32, next: i32) -> i32 {
+    if code == exit_code::SUCCESS {
+        next
+    } else {
+        code
+    }
+}
+
+fn schema_command() -> i32 {
+    match serde_json::to_string_pretty(&config_schema()) {
+        Ok(schema) => {
+            println!("{}", schema);
+            exit_code::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("❌ Error: {}", e);
+            exit_code::USAGE_ERROR
+        }
+    }
+}
+
+/// Validate `--report <FORMAT> <FILE>` into a format and a target file
+fn parse_report_target(report: &Option<Vec<String>>) -> Result<Option<(ReportFormat, String)>, i32> {
+    match report.as_deref() {
+        Some([format, file]) => match ReportFormat::from_str(format, true) {
+            Ok(format) => Ok(Some((format, file.clone()))),
+            Err(_) => {
+                eprintln!("Error: unknown report format '{}', expected json or junit", format);
+                Err(exit_code::USAGE_ERROR)
+            }
+        },
+        _ => Ok(None),
+    }
+}
+
+/// Write the report if one was requested, returning a non-zero code when that fails
+fn save_report(report_target: &Option<(ReportFormat, String)>, report: &RunReport) -> i32 {
+    let Some((format, file_path)) = report_target else {
+        return exit_code::SUCCESS;
+    };
+    match write_report(*format, file_path, report) {
+        Ok(_) => exit_code::SUCCESS,
+        Err(e) => {
+            eprintln!("❌ Error: {}", e);
+            exit_code::USAGE_ERROR
+        }
+    }
+}
+
+/// Get the parser and content paths from either flags or positional arguments.
+/// Returns the parser path as given, its absolute form and the content path.
+fn resolve_input_paths(input: &InputArgs) -> Result<(String, String, String), i32> {
+    let js_file = input.file.clone().or_else(|| input.positional.first().cloned());
+    let content_file = input.content.clone().or_else(|| input.positional.get(1).cloned());
 
     // Validate arguments
     let js_file = match js_file {
         Some(path) => path,
         None => {
             print_js_required();
-            std::process::exit(1);
+            return Err(exit_code::USAGE_ERROR);
         }
     };
 
@@ -30,7 +385,7 @@ fn main() {
         Ok(abs_path) => abs_path,
         Err(e) => {
             eprintln!("Error: Cannot find JavaScript file '{}': {}", js_file, e);
-            std::process::exit(1);
+            return Err(exit_code::USAGE_ERROR);
         }
     };
 
@@ -40,52 +395,258 @@ fn main() {
         Some(path) => path,
         None => {
             print_content_required();
-            std::process::exit(1);
+            return Err(exit_code::USAGE_ERROR);
         }
     };
 
-    // Read JavaScript file
-    let js_code = match fs::read_to_string(&js_file) {
-        Ok(code) => code,
-        Err(e) => {
-            eprintln!("Error reading JavaScript file '{}': {} File path is {}", js_file, e, js_file_str);
-            std::process::exit(1);
-        }
-    };
+    Ok((js_file, js_file_str, content_file))
+}
 
-    // Read content file
-    let content = match fs::read_to_string(&content_file) {
-        Ok(text) => text,
-        Err(e) => {
-            eprintln!("Error reading content file '{}': {}", content_file, e);
-            std::process::exit(1);
-        }
-    };
+/// Run one parser over one content file in the given mode
+fn run_once(
+    mode: RunMode,
+    js_code: &str,
+    content: &str,
+    content_file: &str,
+    js_file_str: &str,
+    generation_config: &Config,
+    report: &mut RunReport,
+) -> i32 {
+    report.config = generation_config.path.clone();
+    if mode != RunMode::Stdout {
+        print_config_source(generation_config.path.as_deref());
+    }
 
-    let generation_config = load_config_from_file();
+    if mode == RunMode::DryRun {
+        let mut skipped = Vec::new();
+        let rendered = render_exported_functions(
+            js_code,
+            content,
+            content_file,
+            js_file_str,
+            generation_config,
+            &mut Vec::new(),
+            &mut skipped,
+        );
+        return match rendered {
+            Ok((rendered, failures)) => {
+                if rendered.len() + failures.len() == 0 {
+                    return nothing_rendered(&skipped, generation_config, false);
+                }
+                print_dry_run_plan(&rendered, &failures);
+                if failures.is_empty() {
+                    exit_code::SUCCESS
+                } else {
+                    exit_code::FUNCTIONS_FAILED
+                }
+            }
+            Err(e) => {
+                eprintln!("❌ Error: {}", e);
+                report.failures.push(parser_failure(js_file_str, &e));
+                exit_code::JS_EVALUATION_FAILED
+            }
+        };
+    }
+
+    if mode == RunMode::Stdout {
+        let mut skipped = Vec::new();
+        let rendered = render_exported_functions(
+            js_code,
+            content,
+            content_file,
+            js_file_str,
+            generation_config,
+            &mut Vec::new(),
+            &mut skipped,
+        );
+        return match rendered {
+            Ok((rendered, failures)) => {
+                if rendered.len() + failures.len() == 0 {
+                    return nothing_rendered(&skipped, generation_config, true);
+                }
+                if let Err(e) = print_rendered_to_stdout(&rendered) {
+                    eprintln!("❌ Error: Failed to write to stdout: {}", e);
+                    return exit_code::USAGE_ERROR;
+                }
+                print_failures_to_stderr(&failures);
+                if failures.is_empty() {
+                    exit_code::SUCCESS
+                } else {
+                    exit_code::FUNCTIONS_FAILED
+                }
+            }
+            Err(e) => {
+                eprintln!("❌ Error: {}", e);
+                report.failures.push(parser_failure(js_file_str, &e));
+                exit_code::JS_EVALUATION_FAILED
+            }
+        };
+    }
+
+    if mode == RunMode::Check {
+        let mut skipped = Vec::new();
+        let result = check_exported_functions(js_code, content, content_file, js_file_str, generation_config, &mut skipped);
+        let nothing_checked = matches!(&result, Ok((checked, failures)) if checked.len() + failures.len() == 0);
+        let code = nothing_checked.then(|| nothing_rendered(&skipped, generation_config, false));
+        report.skipped.extend(skipped);
+        if let Some(code) = code {
+            return code;
+        }
+        return match result {
+            Ok((checked, failures)) => {
+                print_check_report(&checked, &failures);
+                let code = if !failures.is_empty() || checked.iter().any(|c| c.diff.is_some()) {
+                    exit_code::FUNCTIONS_FAILED
+                } else {
+                    exit_code::SUCCESS
+                };
+                add_check_results(report, checked);
+                report.failures.extend(failures);
+                code
+            }
+            Err(e) => {
+                eprintln!("❌ Error: {}", e);
+                report.failures.push(parser_failure(js_file_str, &e));
+                exit_code::JS_EVALUATION_FAILED
+            }
+        };
+    }
 
     // Execute exported functions
-    match execute_exported_functions(&js_code, &content, &js_file_str, &generation_config) {
+    let mut warnings = Vec::new();
+    let mut skipped = Vec::new();
+    let result = execute_exported_functions(
+        js_code,
+        content,
+        content_file,
+        js_file_str,
+        generation_config,
+        &mut warnings,
+        &mut skipped,
+    );
+    report.warnings.extend(warnings);
+    let nothing_written = matches!(&result, Ok((successes, failures)) if successes.len() + failures.len() == 0);
+    let code = nothing_written.then(|| nothing_rendered(&skipped, generation_config, false));
+    report.skipped.extend(skipped);
+    if let Some(code) = code {
+        return code;
+    }
+    match result {
         Ok(results) => {
             let (success_results, failure_results) = results;
-            if success_results.len() + failure_results.len() == 0 {
-                println!("‼️ No functions were exported.");
-                return;
-            }
             println!(" ✅ Executed function(s):\n");
-            for success in success_results {
+            for success in &success_results {
                 println!("\tfunction name: {} with path {}", success.name, success.path);
             }
             println!("\n ❌ Errored function(s):\n");
-            for fail in failure_results {
+            for fail in &failure_results {
                 println!(" function name: {} with path {} and error {}", fail.name, fail.path, fail.error);
             }
+            let code = if failure_results.is_empty() {
+                exit_code::SUCCESS
+            } else {
+                exit_code::FUNCTIONS_FAILED
+            };
+            report.successes.extend(success_results);
+            report.failures.extend(failure_results);
+            code
         }
         Err(e) => {
             eprintln!("❌ Error: {}", e);
-            std::process::exit(1);
+            report.failures.push(parser_failure(js_file_str, &e));
+            exit_code::JS_EVALUATION_FAILED
+        }
+    }
+}
+
+/// Exit code for a render that produced no files and no failures: the parser exports no
+/// functions, `--only` matched no function name or tag, or every function was skipped on
+/// purpose (by `fn.enabled`, `--skip` or `fn.when`), which is not an error
+fn nothing_rendered(skipped: &[SkippedFunction], generation_config: &Config, to_stderr: bool) -> i32 {
+    let only = &generation_config.filter.only;
+    let (message, code) = if skipped.is_empty() {
+        ("‼️ No functions were exported.".to_string(), exit_code::NO_FUNCTIONS)
+    } else if !only.is_empty() && skipped.iter().all(|s| s.reason == SkipReason::NotOnly) {
+        let message = format!("❌ Error: --only {} matched no function name or tag", only.join(", "));
+        (message, exit_code::USAGE_ERROR)
+    } else {
+        let names: Vec<&str> = skipped.iter().map(|s| s.name.as_str()).collect();
+        (format!("⏭️ All functions were skipped: {}", names.join(", ")), exit_code::SUCCESS)
+    };
+    if to_stderr || code == exit_code::USAGE_ERROR {
+        eprintln!("{}", message);
+    } else {
+        println!("{}", message);
+    }
+    code
+}
+
+/// Run every job from rc.config.json, or only those whose name is in `selected`
+fn run_jobs(
+    mode: RunMode,
+    selected: &[String],
+    generation_config: &Config,
+    report: &mut RunReport,
+) -> i32 {
+    let jobs = generation_config.jobs.as_deref().unwrap_or_default();
+    if jobs.is_empty() {
+        eprintln!("Error: rc.config.json does not define any jobs");
+        return exit_code::CONFIG_INVALID;
+    }
+
+    for name in selected {
+        if !jobs.iter().any(|job| &job_name(job) == name) {
+            eprintln!("Error: no job named '{}' in rc.config.json", name);
+            return exit_code::USAGE_ERROR;
         }
     }
+
+    let mut code = exit_code::SUCCESS;
+    for job in jobs {
+        let name = job_name(job);
+        if !selected.is_empty() && !selected.contains(&name) {
+            continue;
+        }
+        let job_config = config_for_job(generation_config, job);
+        let content_files = match &job.content {
+            ContentFiles::One(path) => std::slice::from_ref(path),
+            ContentFiles::Many(paths) => paths.as_slice(),
+        };
+        for content_file in content_files {
+            println!("\n 📦 Job {}: {} with {}\n", name, job.parser, content_file);
+            let job_code = match read_inputs(&job.parser, content_file) {
+                Ok((js_code, content)) => {
+                    run_once(mode, &js_code, &content, content_file, &job.parser, &job_config, report)
+                }
+                Err(e) => {
+                    eprintln!("{}", e);
+                    report.failures.push(parser_failure(&job.parser, &e));
+                    exit_code::USAGE_ERROR
+                }
+            };
+            code = first_failure(code, job_code);
+        }
+    }
+
+    code
 }
 
+/// Content path that means "read the content from stdin"
+const STDIN_PATH: &str = "-";
 
+/// Read the JavaScript parser and the content file (`-` reads the content from stdin)
+fn read_inputs(js_file: &str, content_file: &str) -> Result<(String, String), String> {
+    let js_code = fs::read_to_string(js_file)
+        .map_err(|e| format!("Error reading JavaScript file '{}': {}", js_file, e))?;
+    let content = if content_file == STDIN_PATH {
+        let mut text = String::new();
+        io::stdin()
+            .read_to_string(&mut text)
+            .map_err(|e| format!("Error reading content from stdin: {}", e))?;
+        text
+    } else {
+        fs::read_to_string(content_file)
+            .map_err(|e| format!("Error reading content file '{}': {}", content_file, e))?
+    };
+    Ok((js_code, content))
+}